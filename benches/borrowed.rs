@@ -0,0 +1,68 @@
+//! Owned vs borrowed parsing of a large DynamoDB Streams batch.
+//!
+//! `cargo bench --bench borrowed`, adding `--features simd` to time
+//! `from_slice` with simd-json.
+
+use aws_lambda_events_extended::dynamodb::DynamoDBEvent;
+use serde_json::Value;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const RECORDS: usize = 1000;
+const ITERATIONS: u32 = 200;
+
+// The modify fixture's record repeated, with a distinct ID per record.
+fn batch() -> Vec<u8> {
+    let fixture: Value = serde_json::from_str(include_str!(
+        "../src/fixtures/example-dynamo-stream-modify-event.json"
+    ))
+    .unwrap();
+    let record = &fixture["Records"][0];
+    let records: Vec<Value> = (0..RECORDS)
+        .map(|i| {
+            let mut record = record.clone();
+            record["eventID"] = Value::from(format!("{:032x}", i));
+            record
+        })
+        .collect();
+    serde_json::to_vec(&serde_json::json!({ "Records": records })).unwrap()
+}
+
+fn measure<F: FnMut()>(name: &str, bytes: usize, mut f: F) -> Duration {
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let per_iter = start.elapsed() / ITERATIONS;
+    let throughput = bytes as f64 / per_iter.as_secs_f64() / (1024.0 * 1024.0);
+    println!(
+        "{:<10} {:>10.1?}/batch {:>8.1} MiB/s",
+        name, per_iter, throughput
+    );
+    per_iter
+}
+
+fn main() {
+    let data = batch();
+    println!("{} records, {} bytes", RECORDS, data.len());
+    let owned = measure("owned", data.len(), || {
+        let event: DynamoDBEvent = serde_json::from_slice(black_box(&data)).unwrap();
+        black_box(event);
+    });
+    // serde_json, or simd-json with the `simd` feature.
+    measure("from_slice", data.len(), || {
+        let event = DynamoDBEvent::from_slice(black_box(&data)).unwrap();
+        black_box(event);
+    });
+    let borrowed = measure("borrowed", data.len(), || {
+        let event = DynamoDBEvent::from_slice_borrowed(black_box(&data)).unwrap();
+        black_box(event);
+    });
+    println!(
+        "borrowed takes {:.0}% of the owned time",
+        borrowed.as_secs_f64() / owned.as_secs_f64() * 100.0
+    );
+}
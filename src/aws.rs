@@ -0,0 +1,333 @@
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An Amazon Resource Name, e.g.
+/// `arn:aws:dynamodb:us-east-1:123456789012:table/BarkTable/stream/2016-11-16T20:42:48.104`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Arn {
+    pub partition: String,
+    pub service: String,
+    pub region: String,
+    pub account_id: String,
+    pub resource: String,
+}
+
+impl Arn {
+    /// Splits an ARN into its borrowed `[partition, service, region, account, resource]` parts.
+    pub(crate) fn split(arn: &str) -> Option<[&str; 5]> {
+        let mut parts = arn.splitn(6, ':');
+        if parts.next()? != "arn" {
+            return None;
+        }
+        let partition = parts.next()?;
+        let service = parts.next()?;
+        let region = parts.next()?;
+        let account_id = parts.next()?;
+        let resource = parts.next()?;
+        if partition.is_empty() || service.is_empty() {
+            return None;
+        }
+        Some([partition, service, region, account_id, resource])
+    }
+
+    /// The owning account, or `None` for ARNs without one such as S3 buckets.
+    pub fn account(&self) -> Option<AccountId> {
+        self.account_id.parse().ok()
+    }
+
+    /// The resource type, e.g. `table` for `table/BarkTable/stream/...` or
+    /// `function` for `function:my-function`; `None` for resources that are
+    /// just an ID, such as S3 buckets.
+    pub fn resource_type(&self) -> Option<&str> {
+        let end = self.resource.find(['/', ':'])?;
+        Some(&self.resource[..end])
+    }
+
+    /// The resource without its type, e.g. `BarkTable/stream/...`.
+    pub fn resource_id(&self) -> &str {
+        match self.resource.find(['/', ':']) {
+            Some(end) => &self.resource[end + 1..],
+            None => &self.resource,
+        }
+    }
+}
+
+impl fmt::Display for Arn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arn:{}:{}:{}:{}:{}",
+            self.partition, self.service, self.region, self.account_id, self.resource
+        )
+    }
+}
+
+/// Serialized as the ARN string.
+impl Serialize for Arn {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Arn {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Arn, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl FromStr for Arn {
+    type Err = ArnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [partition, service, region, account_id, resource] =
+            Arn::split(s).ok_or_else(|| ArnError { arn: s.to_string() })?;
+        Ok(Arn {
+            partition: partition.to_string(),
+            service: service.to_string(),
+            region: region.to_string(),
+            account_id: account_id.to_string(),
+            resource: resource.to_string(),
+        })
+    }
+}
+
+/// Returned when a string is not a well-formed ARN.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArnError {
+    arn: String,
+}
+
+impl fmt::Display for ArnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid ARN: {:?}", self.arn)
+    }
+}
+
+impl Error for ArnError {}
+
+/// An AWS region code, e.g. `us-east-1`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Region(Cow<'static, str>);
+
+impl Region {
+    pub const US_EAST_1: Region = Region::from_static("us-east-1");
+    pub const US_EAST_2: Region = Region::from_static("us-east-2");
+    pub const US_WEST_1: Region = Region::from_static("us-west-1");
+    pub const US_WEST_2: Region = Region::from_static("us-west-2");
+    pub const CA_CENTRAL_1: Region = Region::from_static("ca-central-1");
+    pub const SA_EAST_1: Region = Region::from_static("sa-east-1");
+    pub const EU_WEST_1: Region = Region::from_static("eu-west-1");
+    pub const EU_WEST_2: Region = Region::from_static("eu-west-2");
+    pub const EU_WEST_3: Region = Region::from_static("eu-west-3");
+    pub const EU_CENTRAL_1: Region = Region::from_static("eu-central-1");
+    pub const EU_NORTH_1: Region = Region::from_static("eu-north-1");
+    pub const AP_NORTHEAST_1: Region = Region::from_static("ap-northeast-1");
+    pub const AP_NORTHEAST_2: Region = Region::from_static("ap-northeast-2");
+    pub const AP_SOUTHEAST_1: Region = Region::from_static("ap-southeast-1");
+    pub const AP_SOUTHEAST_2: Region = Region::from_static("ap-southeast-2");
+    pub const AP_SOUTH_1: Region = Region::from_static("ap-south-1");
+
+    const fn from_static(code: &'static str) -> Region {
+        Region(Cow::Borrowed(code))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Accepts any well-formed code, not just the known constants, so new
+/// regions work without a crate update.
+impl FromStr for Region {
+    type Err = RegionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = s.split('-').count() >= 3
+            && s.split('-').all(|part| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            });
+        if valid {
+            Ok(Region(Cow::Owned(s.to_string())))
+        } else {
+            Err(RegionError {
+                region: s.to_string(),
+            })
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Region {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Region {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Region, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Returned when a string is not a well-formed region code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionError {
+    region: String,
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid region: {:?}", self.region)
+    }
+}
+
+impl Error for RegionError {}
+
+/// A 12-digit AWS account ID, e.g. `123456789012`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountId(String);
+
+impl AccountId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for AccountId {
+    type Err = AccountIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 12 && s.bytes().all(|b| b.is_ascii_digit()) {
+            Ok(AccountId(s.to_string()))
+        } else {
+            Err(AccountIdError {
+                account_id: s.to_string(),
+            })
+        }
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for AccountId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<AccountId, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Returned when a string is not a 12-digit account ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountIdError {
+    account_id: String,
+}
+
+impl fmt::Display for AccountIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid account ID: {:?}", self.account_id)
+    }
+}
+
+impl Error for AccountIdError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_arn() {
+        let arn: Arn = "arn:aws:dynamodb:us-east-1:123456789012:table/BarkTable/stream/2016-11-16T20:42:48.104"
+            .parse()
+            .unwrap();
+        assert_eq!(arn.partition, "aws");
+        assert_eq!(arn.service, "dynamodb");
+        assert_eq!(arn.region, "us-east-1");
+        assert_eq!(arn.account_id, "123456789012");
+        assert_eq!(
+            arn.resource,
+            "table/BarkTable/stream/2016-11-16T20:42:48.104"
+        );
+        assert!("not-an-arn".parse::<Arn>().is_err());
+        assert_eq!(arn.resource_type(), Some("table"));
+        assert_eq!(
+            arn.resource_id(),
+            "BarkTable/stream/2016-11-16T20:42:48.104"
+        );
+    }
+
+    #[test]
+    fn arn_strings() {
+        let s = "arn:aws:lambda:eu-west-1:123456789012:function:my-function";
+        let arn: Arn = serde_json::from_value(serde_json::json!(s)).unwrap();
+        assert_eq!(arn.to_string(), s);
+        assert_eq!(arn.resource_type(), Some("function"));
+        assert_eq!(serde_json::to_value(&arn).unwrap(), s);
+        assert!(serde_json::from_str::<Arn>(r#""arn:aws""#).is_err());
+
+        let bucket: Arn = "arn:aws:s3:::my-bucket".parse().unwrap();
+        assert_eq!(bucket.resource_type(), None);
+        assert_eq!(bucket.resource_id(), "my-bucket");
+    }
+
+    #[test]
+    fn regions() {
+        let region: Region = "eu-west-1".parse().unwrap();
+        assert_eq!(region, Region::EU_WEST_1);
+        assert_eq!(region.to_string(), "eu-west-1");
+        assert_eq!(
+            serde_json::from_str::<Region>(r#""us-gov-west-1""#)
+                .unwrap()
+                .as_str(),
+            "us-gov-west-1"
+        );
+        for bad in &["", "eu", "eu-west", "EU-WEST-1", "eu--1", "eu west 1"] {
+            assert!(bad.parse::<Region>().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn account_ids() {
+        let id: AccountId = serde_json::from_str(r#""012345678901""#).unwrap();
+        assert_eq!(id.to_string(), "012345678901");
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""012345678901""#);
+        for bad in &["", "12345678901", "1234567890123", "12345678901a"] {
+            assert!(bad.parse::<AccountId>().is_err(), "{:?}", bad);
+        }
+        // Numbers lose leading zeros, so only strings are accepted.
+        assert!(serde_json::from_str::<AccountId>("123456789012").is_err());
+
+        let arn: Arn = "arn:aws:sqs:us-east-1:123456789012:queue".parse().unwrap();
+        assert_eq!(arn.account().unwrap().as_str(), "123456789012");
+        assert_eq!(
+            "arn:aws:s3:::bucket".parse::<Arn>().unwrap().account(),
+            None
+        );
+    }
+}
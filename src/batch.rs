@@ -0,0 +1,192 @@
+//! Partial-batch processing: run a handler per record and report only the
+//! records that failed, so Lambda retries those instead of the whole batch.
+//! Requires `ReportBatchItemFailures` on the event source mapping.
+
+#[cfg(feature = "dynamodb")]
+use crate::dynamodb::{DynamoDBEvent, DynamoDBEventRecord};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// The response Lambda expects from a batch handler.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchResponse {
+    #[serde(rename = "batchItemFailures")]
+    pub batch_item_failures: Vec<BatchItemFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchItemFailure {
+    /// The failed record's identifier: its sequence number for streams, its
+    /// message ID for queues.
+    #[serde(rename = "itemIdentifier")]
+    pub item_identifier: String,
+}
+
+/// Events whose records can be reported individually as failed.
+pub trait BatchEvent {
+    type Record;
+
+    /// Whether the source is an ordered stream. Lambda retries a stream from
+    /// the first reported failure onwards, so processing stops there.
+    const ORDERED: bool;
+
+    fn records(&self) -> &[Self::Record];
+
+    fn into_records(self) -> Vec<Self::Record>;
+
+    fn item_identifier(record: &Self::Record) -> &str;
+}
+
+#[cfg(feature = "dynamodb")]
+impl BatchEvent for DynamoDBEvent {
+    type Record = DynamoDBEventRecord;
+
+    const ORDERED: bool = true;
+
+    fn records(&self) -> &[DynamoDBEventRecord] {
+        &self.records
+    }
+
+    fn into_records(self) -> Vec<DynamoDBEventRecord> {
+        self.records
+    }
+
+    fn item_identifier(record: &DynamoDBEventRecord) -> &str {
+        &record.dynamodb.sequence_number
+    }
+}
+
+/// The result of `process`: the response to return to Lambda, and the
+/// handler error for each reported failure, in the same order.
+#[derive(Debug)]
+pub struct BatchOutcome<E> {
+    pub response: BatchResponse,
+    pub errors: Vec<E>,
+}
+
+impl<E> BatchOutcome<E> {
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Runs `handler` on each record in order. For ordered sources, the first
+/// failure ends processing and is the only one reported; the records after
+/// it are left for the retry.
+pub async fn process<'a, T, F, Fut, E>(event: &'a T, handler: F) -> BatchOutcome<E>
+where
+    T: BatchEvent,
+    F: FnMut(&'a T::Record) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    run::<T, _, _, _, _>(
+        event.records(),
+        |record| T::item_identifier(record),
+        handler,
+    )
+    .await
+}
+
+/// Like `process`, but moves each record into `handler`, for callers that
+/// own the event and would otherwise clone every record.
+pub async fn process_owned<T, F, Fut, E>(event: T, handler: F) -> BatchOutcome<E>
+where
+    T: BatchEvent,
+    F: FnMut(T::Record) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    run::<T, _, _, _, _>(event.into_records(), T::item_identifier, handler).await
+}
+
+async fn run<T, R, F, Fut, E>(
+    records: impl IntoIterator<Item = R>,
+    item_identifier: impl Fn(&R) -> &str,
+    mut handler: F,
+) -> BatchOutcome<E>
+where
+    T: BatchEvent,
+    F: FnMut(R) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    let mut outcome = BatchOutcome {
+        response: BatchResponse::default(),
+        errors: Vec::new(),
+    };
+    for record in records {
+        let failure = BatchItemFailure {
+            item_identifier: item_identifier(&record).to_string(),
+        };
+        if let Err(error) = handler(record).await {
+            outcome.response.batch_item_failures.push(failure);
+            outcome.errors.push(error);
+            if T::ORDERED {
+                break;
+            }
+        }
+    }
+    outcome
+}
+
+#[cfg(all(test, feature = "dynamodb"))]
+pub(crate) mod test {
+    use super::*;
+    use crate::dynamodb::DynamoDBOperationType;
+    use crate::fixtures;
+    use serde_json::json;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    // Polls `future` to completion; the futures under test never wait.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn partial_failures() {
+        let mut event = fixtures::dynamodb::remove_event();
+        event
+            .records
+            .extend(fixtures::dynamodb::insert_event().records);
+        event
+            .records
+            .extend(fixtures::dynamodb::remove_event().records);
+
+        let mut seen = 0;
+        let outcome = block_on(process(&event, |record| {
+            seen += 1;
+            let removed = record.event_name == DynamoDBOperationType::Remove;
+            async move {
+                if removed {
+                    Err("deletes are not supported")
+                } else {
+                    Ok(())
+                }
+            }
+        }));
+        assert_eq!(seen, 1);
+        assert_eq!(outcome.errors, vec!["deletes are not supported"]);
+        assert_eq!(
+            serde_json::to_value(&outcome.response).unwrap(),
+            json!({"batchItemFailures": [{"itemIdentifier": "13021600000000001596893681"}]})
+        );
+
+        let owned = block_on(process_owned(event.clone(), |record| async move {
+            match record.event_name {
+                DynamoDBOperationType::Remove => Err(record.event_id),
+                _ => Ok(()),
+            }
+        }));
+        assert_eq!(owned.response, outcome.response);
+        assert_eq!(owned.errors, vec![event.records[0].event_id.clone()]);
+
+        let outcome = block_on(process(&event, |_| async { Ok::<_, ()>(()) }));
+        assert!(outcome.is_success());
+        assert_eq!(outcome.response, BatchResponse::default());
+    }
+}
@@ -0,0 +1,87 @@
+//! Byte-stable JSON, for snapshot tests and for hashing events to dedup them
+//! by content. Event types hold attribute maps in `HashMap`s, whose iteration
+//! order changes from run to run; the canonical form sorts the keys of every
+//! object, at every depth, and is otherwise compact JSON.
+
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// Serializes the wrapped value in canonical form, e.g. as a field of a
+/// larger payload: `Canonical(&event)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Canonical<T>(pub T);
+
+impl<T: Serialize> Serialize for Canonical<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        to_value(&self.0)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// `value` as a JSON value whose objects have their keys in sorted order.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, serde_json::Error> {
+    serde_json::to_value(value).map(sorted)
+}
+
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(&to_value(value)?)
+}
+
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&to_value(value)?)
+}
+
+// Value's map is already sorted unless serde_json's `preserve_order` feature
+// is enabled somewhere in the dependency graph, in which case it keeps
+// insertion order; inserting in sorted order gives the same result either way.
+pub(crate) fn sorted(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sorted).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn sorts_keys() {
+        let mut headers = HashMap::new();
+        for name in &["x-b", "x-c", "x-a", "x-e", "x-d"] {
+            headers.insert(name.to_string(), json!({"z": 1, "a": [{"y": 2, "b": 3}]}));
+        }
+        let expected = r#"{"x-a":{"a":[{"b":3,"y":2}],"z":1},"x-b":{"a":[{"b":3,"y":2}],"z":1},"x-c":{"a":[{"b":3,"y":2}],"z":1},"x-d":{"a":[{"b":3,"y":2}],"z":1},"x-e":{"a":[{"b":3,"y":2}],"z":1}}"#;
+        assert_eq!(to_string(&headers).unwrap(), expected);
+        assert_eq!(to_vec(&headers).unwrap(), expected.as_bytes());
+        assert_eq!(
+            serde_json::to_string(&json!({"b": Canonical(&headers), "a": null})).unwrap(),
+            format!(r#"{{"a":null,"b":{}}}"#, expected)
+        );
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn stable_events() {
+        let event = crate::fixtures::dynamodb::modify_event();
+        let first = to_vec(&event).unwrap();
+        for _ in 0..10 {
+            let reparsed: crate::dynamodb::DynamoDBEvent = serde_json::from_slice(&first).unwrap();
+            assert_eq!(to_vec(&reparsed).unwrap(), first);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! The invocation context Lambda passes alongside each event, parsed from the
+//! Runtime API's response headers and the function's environment.
+
+use crate::aws::{Arn, ArnError};
+use crate::platform;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Settings that are the same for every invocation, read from the
+/// `AWS_LAMBDA_*` environment variables.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FunctionConfig {
+    pub function_name: String,
+    pub function_version: String,
+    pub memory_limit_mb: u32,
+    pub log_group_name: String,
+    pub log_stream_name: String,
+}
+
+impl FunctionConfig {
+    pub fn from_env() -> FunctionConfig {
+        FunctionConfig::from_vars(env::vars())
+    }
+
+    /// Like `from_env`, reading from the given name/value pairs. Missing or
+    /// malformed variables are left empty (or zero).
+    pub fn from_vars<I, K, V>(vars: I) -> FunctionConfig
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let mut config = FunctionConfig::default();
+        for (name, value) in vars {
+            match name.as_ref() {
+                "AWS_LAMBDA_FUNCTION_NAME" => config.function_name = value.into(),
+                "AWS_LAMBDA_FUNCTION_VERSION" => config.function_version = value.into(),
+                "AWS_LAMBDA_FUNCTION_MEMORY_SIZE" => {
+                    config.memory_limit_mb = value.into().parse().unwrap_or_default()
+                }
+                "AWS_LAMBDA_LOG_GROUP_NAME" => config.log_group_name = value.into(),
+                "AWS_LAMBDA_LOG_STREAM_NAME" => config.log_stream_name = value.into(),
+                _ => {}
+            }
+        }
+        config
+    }
+}
+
+/// The context of a single invocation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LambdaContext {
+    pub request_id: String,
+    /// When the invocation times out, in milliseconds since the Unix epoch.
+    pub deadline_ms: u64,
+    pub invoked_function_arn: String,
+    pub xray_trace_id: Option<String>,
+    /// Set for invocations from the AWS Mobile SDK.
+    pub client_context: Option<ClientContext>,
+    /// Set for invocations from the AWS Mobile SDK with a Cognito identity.
+    pub identity: Option<CognitoIdentity>,
+    pub config: FunctionConfig,
+}
+
+impl LambdaContext {
+    /// Builds the context from the headers of a Runtime API
+    /// `/invocation/next` response. Header names are matched
+    /// case-insensitively; unrelated headers are ignored.
+    pub fn from_headers<'a, I>(
+        headers: I,
+        config: FunctionConfig,
+    ) -> Result<LambdaContext, ContextError>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        fn invalid(header: &'static str, value: &str) -> ContextError {
+            ContextError::Invalid {
+                header,
+                value: value.to_string(),
+            }
+        }
+
+        let mut request_id = None;
+        let mut deadline_ms = None;
+        let mut invoked_function_arn = None;
+        let mut context = LambdaContext {
+            config,
+            ..LambdaContext::default()
+        };
+        for (name, value) in headers {
+            match name.to_ascii_lowercase().as_str() {
+                "lambda-runtime-aws-request-id" => request_id = Some(value.to_string()),
+                "lambda-runtime-deadline-ms" => {
+                    let deadline = value.parse();
+                    deadline_ms =
+                        Some(deadline.map_err(|_| invalid("Lambda-Runtime-Deadline-Ms", value))?)
+                }
+                "lambda-runtime-invoked-function-arn" => {
+                    invoked_function_arn = Some(value.to_string())
+                }
+                "lambda-runtime-trace-id" => context.xray_trace_id = Some(value.to_string()),
+                "lambda-runtime-client-context" => {
+                    let client_context = serde_json::from_str(value);
+                    context.client_context = Some(
+                        client_context
+                            .map_err(|_| invalid("Lambda-Runtime-Client-Context", value))?,
+                    )
+                }
+                "lambda-runtime-cognito-identity" => {
+                    let identity = serde_json::from_str(value);
+                    context.identity = Some(
+                        identity.map_err(|_| invalid("Lambda-Runtime-Cognito-Identity", value))?,
+                    )
+                }
+                _ => {}
+            }
+        }
+        context.request_id =
+            request_id.ok_or(ContextError::Missing("Lambda-Runtime-Aws-Request-Id"))?;
+        context.deadline_ms =
+            deadline_ms.ok_or(ContextError::Missing("Lambda-Runtime-Deadline-Ms"))?;
+        context.invoked_function_arn = invoked_function_arn
+            .ok_or(ContextError::Missing("Lambda-Runtime-Invoked-Function-Arn"))?;
+        Ok(context)
+    }
+
+    pub fn deadline(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.deadline_ms)
+    }
+
+    /// The time left before the invocation times out, zero once it has passed.
+    /// `None` on targets without a clock.
+    pub fn remaining_time(&self) -> Option<Duration> {
+        self.deadline()
+            .duration_since(platform::now()?)
+            .ok()
+            .or(Some(Duration::default()))
+    }
+
+    /// The parsed invoked function ARN, which includes the alias or version
+    /// qualifier when the function was invoked through one.
+    pub fn invoked_arn(&self) -> Result<Arn, ArnError> {
+        self.invoked_function_arn.parse()
+    }
+}
+
+/// Client and device information from the AWS Mobile SDK.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientContext {
+    #[serde(rename = "client", default)]
+    pub client: ClientApplication,
+    #[serde(rename = "custom", default)]
+    pub custom: HashMap<String, String>,
+    #[serde(rename = "env", default)]
+    pub environment: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientApplication {
+    #[serde(rename = "installationId", default)]
+    pub installation_id: String,
+    #[serde(rename = "appTitle", default)]
+    pub app_title: String,
+    #[serde(rename = "appVersionName", default)]
+    pub app_version_name: String,
+    #[serde(rename = "appVersionCode", default)]
+    pub app_version_code: String,
+    #[serde(rename = "appPackageName", default)]
+    pub app_package_name: String,
+}
+
+/// The Amazon Cognito identity that authorized the invocation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CognitoIdentity {
+    #[serde(rename = "cognitoIdentityId")]
+    pub identity_id: String,
+    #[serde(rename = "cognitoIdentityPoolId")]
+    pub identity_pool_id: String,
+}
+
+/// Returned when the Runtime API headers are missing a required header or
+/// contain a malformed one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextError {
+    Missing(&'static str),
+    Invalid { header: &'static str, value: String },
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::Missing(header) => write!(f, "missing {} header", header),
+            ContextError::Invalid { header, value } => {
+                write!(f, "invalid {} header: {:?}", header, value)
+            }
+        }
+    }
+}
+
+impl Error for ContextError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn context_from_headers() {
+        let config = FunctionConfig::from_vars(vec![
+            ("AWS_LAMBDA_FUNCTION_NAME", "my-function"),
+            ("AWS_LAMBDA_FUNCTION_VERSION", "$LATEST"),
+            ("AWS_LAMBDA_FUNCTION_MEMORY_SIZE", "128"),
+            ("HOME", "/"),
+        ]);
+        assert_eq!(config.memory_limit_mb, 128);
+
+        let headers = vec![
+            (
+                "Lambda-Runtime-Aws-Request-Id",
+                "8476a536-e9f4-11e8-9739-2dfe598c3fcd",
+            ),
+            ("lambda-runtime-deadline-ms", "1542409706888"),
+            (
+                "Lambda-Runtime-Invoked-Function-Arn",
+                "arn:aws:lambda:us-east-2:123456789012:function:my-function:prod",
+            ),
+            (
+                "Lambda-Runtime-Trace-Id",
+                "Root=1-5bef4de7-ad49b0e87f6ef6c87fc2e700",
+            ),
+            (
+                "Lambda-Runtime-Cognito-Identity",
+                r#"{"cognitoIdentityId":"id","cognitoIdentityPoolId":"pool"}"#,
+            ),
+            ("Content-Type", "application/json"),
+        ];
+        let context = LambdaContext::from_headers(headers.clone(), config.clone()).unwrap();
+        assert_eq!(context.request_id, "8476a536-e9f4-11e8-9739-2dfe598c3fcd");
+        assert_eq!(
+            context.deadline(),
+            UNIX_EPOCH + Duration::from_millis(1542409706888)
+        );
+        assert_eq!(context.remaining_time(), Some(Duration::default()));
+        assert_eq!(
+            context.invoked_arn().unwrap().resource_id(),
+            "my-function:prod"
+        );
+        assert_eq!(context.identity.unwrap().identity_pool_id, "pool");
+        assert_eq!(context.client_context, None);
+        assert_eq!(context.config, config);
+
+        assert_eq!(
+            LambdaContext::from_headers(headers[1..].to_vec(), config.clone()),
+            Err(ContextError::Missing("Lambda-Runtime-Aws-Request-Id"))
+        );
+        let mut bad = headers;
+        bad[1].1 = "soon";
+        assert_eq!(
+            LambdaContext::from_headers(bad, config)
+                .unwrap_err()
+                .to_string(),
+            r#"invalid Lambda-Runtime-Deadline-Ms header: "soon""#
+        );
+    }
+}
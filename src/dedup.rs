@@ -0,0 +1,111 @@
+//! Deduplication keys for event records, and a bounded cache of keys already
+//! handled, for idempotent handlers that may see the same record twice
+//! (retries, replays, overlapping batches).
+
+use crate::dynamodb::DynamoDBEventRecord;
+use std::collections::{HashSet, VecDeque};
+
+/// A record's unique, stable identifier within its source.
+pub trait DedupKey {
+    fn dedup_key(&self) -> &str;
+}
+
+/// DynamoDB Streams' eventID, which is unique per stream record and stays the
+/// same when a batch is retried.
+impl DedupKey for DynamoDBEventRecord {
+    fn dedup_key(&self) -> &str {
+        &self.event_id
+    }
+}
+
+/// An in-memory set of the most recently seen keys, evicting the oldest once
+/// `capacity` is reached. Suitable for state kept across warm invocations.
+#[derive(Debug, Clone)]
+pub struct SeenCache {
+    capacity: usize,
+    keys: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl SeenCache {
+    pub fn new(capacity: usize) -> SeenCache {
+        SeenCache {
+            capacity,
+            keys: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Records `key` as seen, returning true if it had not been seen before.
+    pub fn insert(&mut self, key: &str) -> bool {
+        if self.keys.contains(key) {
+            return false;
+        }
+        if self.capacity == 0 {
+            return true;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+        self.keys.insert(key.to_string());
+        self.order.push_back(key.to_string());
+        true
+    }
+
+    /// Records `record` as seen, returning true if it had not been seen before.
+    pub fn first_seen<T: DedupKey + ?Sized>(&mut self, record: &T) -> bool {
+        self.insert(record.dedup_key())
+    }
+
+    /// The records not seen before, in order, marking them as seen.
+    pub fn unseen<'a, T, I>(&mut self, records: I) -> Vec<&'a T>
+    where
+        T: DedupKey + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        records
+            .into_iter()
+            .filter(|record| self.first_seen(*record))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn seen_cache() {
+        let mut event = fixtures::dynamodb::insert_event();
+        event
+            .records
+            .extend(fixtures::dynamodb::insert_event().records);
+        event
+            .records
+            .extend(fixtures::dynamodb::modify_event().records);
+
+        let mut cache = SeenCache::new(2);
+        assert_eq!(cache.unseen(&event.records).len(), 2);
+        assert!(cache.unseen(&event.records).is_empty());
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache.insert("new"));
+        assert!(!cache.contains(&event.records[0].event_id));
+        assert!(cache.contains(&event.records[2].event_id));
+    }
+}
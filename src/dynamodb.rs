@@ -1,18 +1,180 @@
+use crate::aws::{AccountId, Arn, ArnError, Region};
+use crate::error::{self, EventParseError};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+
+mod age;
+mod attribute;
+mod binary;
+mod borrowed;
+mod builder;
+mod diff;
+mod enums;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+mod ignored;
+#[cfg(feature = "interop")]
+mod interop;
+mod item;
+mod key;
+mod key_schema;
+mod kinesis;
+mod number;
+pub mod outbox;
+mod patch;
+mod path;
+mod raw;
+mod record_error;
+#[cfg(feature = "rusoto")]
+mod rusoto;
+mod sample;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(feature = "aws-sdk")]
+mod sdk;
+mod shard;
+mod size;
+mod stream;
+mod strict;
+mod user_identity;
+mod validate;
+
+pub use crate::encoding::base64::{BinaryEncoding, WithBinaryEncoding};
+pub use borrowed::{
+    AttributeValueRef, DynamoDBEventRecordRef, DynamoDBEventRef, DynamoDBStreamRecordRef,
+};
+pub use builder::{DynamoDBEventBuilder, DynamoDBEventRecordBuilder, DEFAULT_STREAM_VIEW_TYPE};
+pub use diff::{AttributeChange, AttributePath, ImageDiff, PathSegment};
+pub use enums::ParseEnumError;
+#[cfg(feature = "interop")]
+pub use interop::InteropError;
+pub use item::{from_item, item_to_json, item_to_json_lossy, json_to_item};
+pub use key::ItemKey;
+pub use key_schema::{KeySchema, KeySchemaElement};
+pub use kinesis::{KinesisPayload, KinesisStreamRecord};
+pub use number::DynamoDBNumber;
+pub use patch::PatchOperation;
+pub use path::{ItemPath, ParsePathError};
+pub use raw::{RawDynamoDBEvent, RawDynamoDBEventRecord};
+pub use record_error::RecordError;
+#[cfg(feature = "rusoto")]
+pub use rusoto::{from_rusoto_item, to_rusoto_item};
+#[cfg(feature = "aws-sdk")]
+pub use sdk::{from_sdk_item, to_sdk_item, SdkConversionError};
+pub use size::item_size;
+pub use stream::RecordsReader;
+pub use validate::{validate_item, ValidationError, ValidationErrorKind};
+
 // The DynamoDBEvent stream event handled to Lambda
 // http://docs.aws.amazon.com/lambda/latest/dg/eventsources.html#eventsources-ddb-update
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct DynamoDBEvent {
     #[serde(rename = "Records")]
     pub records: Vec<DynamoDBEventRecord>,
 }
 
+impl DynamoDBEvent {
+    pub fn new(records: Vec<DynamoDBEventRecord>) -> DynamoDBEvent {
+        DynamoDBEvent { records }
+    }
+
+    // Parses an event, with errors that locate the offending field.
+    pub fn from_slice(input: &[u8]) -> Result<DynamoDBEvent, EventParseError> {
+        error::from_slice_owned("dynamodb", input)
+    }
+
+    pub fn from_value(value: serde_json::Value) -> Result<DynamoDBEvent, EventParseError> {
+        error::from_value("dynamodb", &value)
+    }
+
+    // Records with the given eventName.
+    pub fn by_operation(
+        &self,
+        operation: DynamoDBOperationType,
+    ) -> impl Iterator<Item = &DynamoDBEventRecord> + '_ {
+        self.records
+            .iter()
+            .filter(move |record| record.event_name == operation)
+    }
+
+    pub fn inserts(&self) -> impl Iterator<Item = &DynamoDBEventRecord> + '_ {
+        self.by_operation(DynamoDBOperationType::Insert)
+    }
+
+    pub fn modifies(&self) -> impl Iterator<Item = &DynamoDBEventRecord> + '_ {
+        self.by_operation(DynamoDBOperationType::Modify)
+    }
+
+    pub fn removes(&self) -> impl Iterator<Item = &DynamoDBEventRecord> + '_ {
+        self.by_operation(DynamoDBOperationType::Remove)
+    }
+
+    // The Keys of every record, deserialized into `K` (see `from_item`).
+    pub fn keys_of<K>(&self) -> impl Iterator<Item = Result<K, serde_json::Error>> + '_
+    where
+        K: DeserializeOwned,
+    {
+        let empty = HashMap::new();
+        self.records
+            .iter()
+            .map(move |record| from_item(record.dynamodb.keys.as_ref().unwrap_or(&empty)))
+    }
+
+    // The latest record for each primary key, ordered by SequenceNumber. Useful
+    // for compacting a batch down to the final state of every item it touched.
+    pub fn group_by_key(&self) -> Vec<&DynamoDBEventRecord> {
+        let mut latest: HashMap<ItemKey, &DynamoDBEventRecord> = HashMap::new();
+        for record in &self.records {
+            let key = record.dynamodb.key();
+            let replace = match latest.get(&key) {
+                Some(current) => {
+                    compare_sequence_numbers(
+                        &record.dynamodb.sequence_number,
+                        &current.dynamodb.sequence_number,
+                    ) != Ordering::Less
+                }
+                None => true,
+            };
+            if replace {
+                latest.insert(key, record);
+            }
+        }
+        let mut records: Vec<&DynamoDBEventRecord> = latest.into_values().collect();
+        records.sort_by(|a, b| {
+            compare_sequence_numbers(&a.dynamodb.sequence_number, &b.dynamodb.sequence_number)
+        });
+        records
+    }
+}
+
+// Compares two SequenceNumbers as the arbitrarily large integers they are,
+// rather than as strings ("9" < "10") or as a lossy fixed-width number.
+pub fn compare_sequence_numbers(a: &str, b: &str) -> Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
 // DynamoDbEventRecord stores information about each record of a DynamoDb stream event
+//
+// Only eventID, eventName and the dynamodb body are required; the other fields
+// default to empty, as they are often missing from emulator and trimmed
+// payloads.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+// With both strict and extra-fields on, unknown fields are captured in `extra`
+// rather than rejected; `from_slice_strict` still reports them.
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
 pub struct DynamoDBEventRecord {
     // The region in which the GetRecords request was received.
-    #[serde(rename = "awsRegion")]
+    #[serde(default, rename = "awsRegion")]
     pub aws_region: String,
 
     // The main body of the stream record, containing all of the DynamoDB-specific
@@ -37,7 +199,7 @@ pub struct DynamoDBEventRecord {
 
     // The AWS service from which the stream record originated. For DynamoDB Streams,
     // this is aws:dynamodb.
-    #[serde(rename = "eventSource")]
+    #[serde(default, rename = "eventSource")]
     pub event_source: String,
 
     // The version number of the stream record format. This number is updated whenever
@@ -46,11 +208,11 @@ pub struct DynamoDBEventRecord {
     // Client applications must not assume that eventVersion will remain at a particular
     // value, as this number is subject to change at any time. In general, eventVersion
     // will only increase as the low-level DynamoDB Streams API evolves.
-    #[serde(rename = "eventVersion")]
+    #[serde(default, rename = "eventVersion")]
     pub event_version: String,
 
     // The event source ARN of DynamoDB
-    #[serde(rename = "eventSourceARN")]
+    #[serde(default, rename = "eventSourceARN")]
     pub event_source_arn: String,
 
     // Items that are deleted by the Time to Live process after expiration have
@@ -65,22 +227,128 @@ pub struct DynamoDBEventRecord {
     // "dynamodb.amazonaws.com"
     #[serde(rename = "userIdentity")]
     pub user_identity: Option<DynamoDBUserIdentity>,
+
+    // Any fields not modelled above, kept so they survive a roundtrip.
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub struct DynamoDBUserIdentity {
-    #[serde(rename = "type")]
-    pub r#type: String,
-    #[serde(rename = "principalId")]
-    pub principal_id: String,
+// An INSERT with eventSource and eventVersion set as DynamoDB Streams sends
+// them, and everything else empty.
+impl Default for DynamoDBEventRecord {
+    fn default() -> DynamoDBEventRecord {
+        DynamoDBEventRecord {
+            aws_region: String::new(),
+            dynamodb: DynamoDBStreamRecord::default(),
+            event_id: String::new(),
+            event_name: DynamoDBOperationType::Insert,
+            event_source: "aws:dynamodb".to_string(),
+            event_version: "1.1".to_string(),
+            event_source_arn: String::new(),
+            user_identity: None,
+            #[cfg(feature = "extra-fields")]
+            extra: HashMap::new(),
+        }
+    }
+}
+
+impl DynamoDBEventRecord {
+    // A record with the given identity and stream record. awsRegion is taken
+    // from the event source ARN.
+    pub fn new<I, A>(
+        event_id: I,
+        event_name: DynamoDBOperationType,
+        event_source_arn: A,
+        dynamodb: DynamoDBStreamRecord,
+    ) -> DynamoDBEventRecord
+    where
+        I: Into<String>,
+        A: Into<String>,
+    {
+        let event_source_arn = event_source_arn.into();
+        DynamoDBEventRecord {
+            aws_region: Arn::split(&event_source_arn)
+                .map(|[_, _, region, _, _]| region.to_string())
+                .unwrap_or_default(),
+            dynamodb,
+            event_id: event_id.into(),
+            event_name,
+            event_source_arn,
+            ..Default::default()
+        }
+    }
+
+    // The parsed event source ARN.
+    pub fn arn(&self) -> Result<Arn, ArnError> {
+        self.event_source_arn.parse()
+    }
+
+    // The name of the table the stream belongs to, e.g. "BarkTable".
+    pub fn table_name(&self) -> Option<&str> {
+        self.arn_resource()?.split('/').nth(1)
+    }
+
+    // The stream label, e.g. "2016-11-16T20:42:48.104".
+    pub fn stream_label(&self) -> Option<&str> {
+        let resource = self.arn_resource()?;
+        let start = resource.find("/stream/")? + "/stream/".len();
+        Some(&resource[start..])
+    }
+
+    // The awsRegion field as a Region; None if it is malformed. This is where
+    // the GetRecords request was received, which may differ from arn_region
+    // for global tables.
+    pub fn aws_region(&self) -> Option<Region> {
+        self.aws_region.parse().ok()
+    }
+
+    // The region of the stream, from the event source ARN; None if it is
+    // missing or malformed.
+    pub fn arn_region(&self) -> Option<Region> {
+        Arn::split(&self.event_source_arn)?[2].parse().ok()
+    }
+
+    // The account owning the stream, from the event source ARN; None if it is
+    // missing or malformed.
+    pub fn account_id(&self) -> Option<AccountId> {
+        Arn::split(&self.event_source_arn)?[3].parse().ok()
+    }
+
+    fn arn_resource(&self) -> Option<&str> {
+        Arn::split(&self.event_source_arn).map(|[_, _, _, _, resource]| resource)
+    }
+}
+
+// Who performed the change, when it wasn't an application write. DynamoDB
+// only sets this for TTL deletions, which arrive as Service with the principal
+// "dynamodb.amazonaws.com"; see user_identity.rs for the wire format.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynamoDBUserIdentity {
+    Service {
+        principal_id: String,
+    },
+    Other {
+        r#type: String,
+        principal_id: String,
+    },
 }
 
 // DynamoDBStreamRecord represents a description of a single data modification that was performed on an item
 // in a DynamoDB table.
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
 pub struct DynamoDBStreamRecord {
     // The approximate date and time when the stream record was created, in UNIX
-    // epoch time (http://www.epochconverter.com/) format.
+    // epoch time (http://www.epochconverter.com/) format. This stays an f64
+    // with the `chrono` feature too, since changing a field's type by feature
+    // would break other crates in the same build; a typed field with
+    // `time::float_epoch::option` is deferred to the next breaking release.
+    // Use `approximate_creation_time` for a `DateTime<Utc>` meanwhile.
     #[serde(rename = "ApproximateCreationDateTime")]
     pub approximate_creation_date_time: Option<f64>,
 
@@ -101,46 +369,92 @@ pub struct DynamoDBStreamRecord {
     pub sequence_number: String,
 
     // The size of the stream record, in bytes.
-    #[serde(rename = "SizeBytes")]
+    #[serde(default, rename = "SizeBytes")]
     pub size_bytes: u64,
 
     // The type of data from the modified DynamoDB item that was captured in this
     // stream record.
-    #[serde(rename = "StreamViewType")]
+    #[serde(default, rename = "StreamViewType")]
     pub stream_view_type: String,
+
+    // Any fields not modelled above, kept so they survive a roundtrip.
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "UPPERCASE")]
+impl DynamoDBStreamRecord {
+    pub fn new<S: Into<String>>(
+        sequence_number: S,
+        keys: HashMap<String, AttributeValue>,
+    ) -> DynamoDBStreamRecord {
+        DynamoDBStreamRecord {
+            keys: Some(keys),
+            sequence_number: sequence_number.into(),
+            ..Default::default()
+        }
+    }
+
+    // The attributes added, removed, and changed between OldImage and NewImage.
+    // A missing image is treated as an empty item, so an INSERT reports every
+    // attribute as added and a REMOVE reports every attribute as removed.
+    pub fn diff(&self) -> ImageDiff {
+        let empty = HashMap::new();
+        ImageDiff::between(
+            self.old_image.as_ref().unwrap_or(&empty),
+            self.new_image.as_ref().unwrap_or(&empty),
+        )
+    }
+
+    // ApproximateCreationDateTime as a UTC timestamp, rather than fractional epoch seconds.
+    #[cfg(feature = "chrono")]
+    pub fn approximate_creation_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.approximate_creation_date_time
+            .and_then(crate::time::from_float_epoch)
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn set_approximate_creation_time(&mut self, time: Option<chrono::DateTime<chrono::Utc>>) {
+        self.approximate_creation_date_time = time.as_ref().map(crate::time::to_float_epoch);
+    }
+}
+
+// Values AWS adds after this crate was released deserialize as Unknown rather
+// than failing the whole batch; see enums.rs for the wire strings.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum DynamoDBKeyType {
     Hash,
     Range,
+    Unknown(String),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "UPPERCASE")]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum DynamoDBOperationType {
     Insert,
     Modify,
     Remove,
+    Unknown(String),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum DynamoDBStreamViewType {
-    #[serde(rename = "NEW_IMAGE")]
     NewImage,
-    #[serde(rename = "OLD_IMAGE")]
     OldImage,
-    #[serde(rename = "NEW_AND_OLD_IMAGE")]
     NewAndOldImage,
-    #[serde(rename = "KEYS_ONLY")]
     KeysOnly,
+    Unknown(String),
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+// PartialEq and Hash are implemented in key.rs, as sets compare unordered.
+#[derive(Default, Debug, Clone, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct AttributeValue {
     /// <p>An attribute of type Binary. For example:</p> <p> <code>"B": "dGhpcyB0ZXh0IGlzIGJhc2U2NC1lbmNvZGVk"</code> </p>
     #[serde(rename = "B")]
+    #[serde(default, with = "crate::encoding::base64::option")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub b: Option<bytes::Bytes>,
     /// <p>An attribute of type Boolean. For example:</p> <p> <code>"BOOL": true</code> </p>
@@ -149,6 +463,7 @@ pub struct AttributeValue {
     pub bool: Option<bool>,
     /// <p>An attribute of type Binary Set. For example:</p> <p> <code>"BS": ["U3Vubnk=", "UmFpbnk=", "U25vd3k="]</code> </p>
     #[serde(rename = "BS")]
+    #[serde(default, with = "crate::encoding::base64::option_vec")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bs: Option<Vec<bytes::Bytes>>,
     /// <p>An attribute of type List. For example:</p> <p> <code>"L": [ {"S": "Cookies"} , {"S": "Coffee"}, {"N", "3.14159"}]</code> </p>
@@ -195,4 +510,180 @@ mod test {
         let reparsed: DynamoDBEvent = serde_json::from_slice(output.as_bytes()).unwrap();
         assert_eq!(parsed, reparsed);
     }
+
+    #[test]
+    fn attribute_value_wire_format() {
+        let value: AttributeValue = serde_json::from_str(
+            r#"{"M": {"Name": {"S": "Joe"}, "Avatar": {"B": "cG5n"}, "Tags": {"SS": ["a"]},
+                "Scores": {"L": [{"N": "1"}, {"NULL": true}]}}}"#,
+        )
+        .unwrap();
+        let m = value.as_m().unwrap();
+        assert_eq!(m["Name"], AttributeValue::s("Joe"));
+        assert_eq!(m["Avatar"], AttributeValue::b(&b"png"[..]));
+        assert_eq!(m["Tags"], AttributeValue::ss(vec!["a"]));
+        assert_eq!(
+            m["Scores"],
+            AttributeValue::l(vec![AttributeValue::n(1), AttributeValue::null()])
+        );
+
+        let explicit_null: AttributeValue = serde_json::from_str(r#"{"S": null}"#).unwrap();
+        assert_eq!(explicit_null, AttributeValue::default());
+
+        let duplicate = serde_json::from_str::<AttributeValue>(r#"{"S": "a", "S": "b"}"#);
+        assert!(duplicate
+            .unwrap_err()
+            .to_string()
+            .contains("duplicate field `S`"));
+
+        let unknown = serde_json::from_str::<AttributeValue>(r#"{"S": "a", "X": [1]}"#);
+        assert_eq!(unknown.is_err(), cfg!(feature = "strict"));
+    }
+
+    #[test]
+    fn example_arn_accessors() {
+        let data = include_bytes!("fixtures/example-dynamo-stream-event.json");
+        let parsed: DynamoDBEvent = serde_json::from_slice(data).unwrap();
+        let record = &parsed.records[0];
+        assert_eq!(record.table_name(), Some("BarkTable"));
+        assert_eq!(record.stream_label(), Some("2016-11-16T20:42:48.104"));
+        // The fixture's placeholder "region" is not a well-formed region.
+        assert_eq!(record.arn_region(), None);
+        assert_eq!(
+            record.account_id().as_ref().map(AccountId::as_str),
+            Some("123456789012")
+        );
+        assert_eq!(record.arn().unwrap().service, "dynamodb");
+
+        let mut record = DynamoDBEventRecord::new(
+            "1",
+            DynamoDBOperationType::Insert,
+            "arn:aws:dynamodb:eu-west-1:123456789012:table/BarkTable/stream/label",
+            DynamoDBStreamRecord::default(),
+        );
+        record.aws_region = "us-east-1".to_string();
+        assert_eq!(record.arn_region(), Some(Region::EU_WEST_1));
+        assert_eq!(record.aws_region(), Some(Region::US_EAST_1));
+    }
+
+    #[test]
+    fn example_record_filters() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Key {
+            #[serde(rename = "Username")]
+            username: String,
+            #[serde(rename = "Timestamp")]
+            timestamp: String,
+        }
+
+        let data = include_bytes!("fixtures/example-dynamo-stream-event.json");
+        let parsed: DynamoDBEvent = serde_json::from_slice(data).unwrap();
+        assert_eq!(parsed.inserts().count(), 1);
+        assert_eq!(parsed.modifies().count(), 0);
+        assert_eq!(parsed.removes().count(), 0);
+        let keys: Vec<Key> = parsed.keys_of().collect::<Result<_, _>>().unwrap();
+        assert_eq!(keys[0].username, "John Doe");
+        assert_eq!(keys[0].timestamp, "2016-11-18:12:09:36");
+    }
+
+    #[test]
+    fn binary_attribute_values_are_base64() {
+        let value: AttributeValue = serde_json::from_str(
+            r#"{"B": "dGhpcyB0ZXh0IGlzIGJhc2U2NC1lbmNvZGVk", "BS": ["U3Vubnk=", "UmFpbnk="]}"#,
+        )
+        .unwrap();
+        assert_eq!(value.as_bytes(), Some(&b"this text is base64-encoded"[..]));
+        assert_eq!(value.as_bs().unwrap()[1], &b"Rainy"[..]);
+        let output = serde_json::to_string(&value).unwrap();
+        assert_eq!(
+            output,
+            r#"{"B":"dGhpcyB0ZXh0IGlzIGJhc2U2NC1lbmNvZGVk","BS":["U3Vubnk=","UmFpbnk="]}"#
+        );
+        assert!(serde_json::from_str::<AttributeValue>(r#"{"B": "not base64!"}"#).is_err());
+    }
+
+    #[test]
+    fn sequence_number_ordering() {
+        assert_eq!(compare_sequence_numbers("9", "10"), Ordering::Less);
+        assert_eq!(compare_sequence_numbers("0010", "10"), Ordering::Equal);
+        assert_eq!(
+            compare_sequence_numbers("13021600000000001596893679", "13021600000000001596893678"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn constructors() {
+        let mut keys = HashMap::new();
+        keys.insert("Id".to_string(), AttributeValue::s("1"));
+        let record = DynamoDBEventRecord::new(
+            "1",
+            DynamoDBOperationType::Modify,
+            "arn:aws:dynamodb:eu-west-1:123456789012:table/T/stream/2020",
+            DynamoDBStreamRecord::new("100", keys.clone()),
+        );
+        assert_eq!(record.aws_region, "eu-west-1");
+        assert_eq!(record.aws_region(), Some(Region::EU_WEST_1));
+        assert_eq!(record.event_source, "aws:dynamodb");
+        assert_eq!(record.dynamodb.keys, Some(keys));
+        assert_eq!(record.table_name(), Some("T"));
+
+        let event = DynamoDBEvent::new(vec![record]);
+        assert_eq!(event.records.len(), 1);
+        assert!(DynamoDBEvent::default().records.is_empty());
+    }
+
+    #[test]
+    fn example_group_by_key() {
+        let data = include_bytes!("fixtures/example-dynamo-stream-event.json");
+        let mut parsed: DynamoDBEvent = serde_json::from_slice(data).unwrap();
+        let mut update = parsed.records[0].clone();
+        update.event_name = DynamoDBOperationType::Modify;
+        update.dynamodb.sequence_number = "13021600000000001596893680".to_string();
+        parsed.records.insert(0, update);
+
+        let latest = parsed.group_by_key();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].event_name, DynamoDBOperationType::Modify);
+    }
+
+    #[test]
+    fn example_insert_diff() {
+        let data = include_bytes!("fixtures/example-dynamo-stream-event.json");
+        let parsed: DynamoDBEvent = serde_json::from_slice(data).unwrap();
+        let diff = parsed.records[0].dynamodb.diff();
+        assert_eq!(diff.added.len(), 3);
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+    }
+
+    #[cfg(feature = "extra-fields")]
+    #[test]
+    fn unknown_fields_roundtrip() {
+        let data = include_bytes!("fixtures/example-dynamo-stream-event.json");
+        let mut value: serde_json::Value = serde_json::from_slice(data).unwrap();
+        value["Records"][0]["recordFormat"] = "application/json".into();
+        value["Records"][0]["dynamodb"]["ApproximateCreationDateTimePrecision"] =
+            "MILLISECOND".into();
+        let parsed: DynamoDBEvent = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(parsed.records[0].extra["recordFormat"], "application/json");
+        let output = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(output["Records"][0]["recordFormat"], "application/json");
+        assert_eq!(
+            output["Records"][0]["dynamodb"]["ApproximateCreationDateTimePrecision"],
+            "MILLISECOND"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn example_approximate_creation_time() {
+        let data = include_bytes!("fixtures/example-dynamo-stream-event.json");
+        let parsed: DynamoDBEvent = serde_json::from_slice(data).unwrap();
+        let time = parsed.records[0]
+            .dynamodb
+            .approximate_creation_time()
+            .unwrap();
+        assert_eq!(time.timestamp(), 1479499740);
+    }
 }
@@ -0,0 +1,98 @@
+use super::{DynamoDBEvent, DynamoDBEventRecord, DynamoDBStreamRecord};
+use crate::platform;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+impl DynamoDBStreamRecord {
+    // ApproximateCreationDateTime as a SystemTime, for builds without chrono;
+    // None for negative times and ones past what SystemTime can hold.
+    pub(crate) fn created_at(&self) -> Option<SystemTime> {
+        let seconds = self.approximate_creation_date_time?;
+        UNIX_EPOCH.checked_add(Duration::try_from_secs_f64(seconds).ok()?)
+    }
+}
+
+impl DynamoDBEventRecord {
+    /// How long before `now` the change was made, from the stream record's
+    /// ApproximateCreationDateTime; zero for times after `now`, through clock
+    /// skew, and `None` for records without one.
+    pub fn approximate_age(&self, now: SystemTime) -> Option<Duration> {
+        let created = self.dynamodb.created_at()?;
+        Some(now.duration_since(created).unwrap_or_default())
+    }
+}
+
+impl DynamoDBEvent {
+    /// Drops the records older than `max_age`, such as a backlog replayed
+    /// after an outage, and returns how many were dropped. Records of unknown
+    /// age are kept, as are all records on targets without a clock.
+    pub fn drop_older_than(&mut self, max_age: Duration) -> usize {
+        match platform::now() {
+            Some(now) => self.drop_older_than_at(max_age, now),
+            None => 0,
+        }
+    }
+
+    fn drop_older_than_at(&mut self, max_age: Duration, now: SystemTime) -> usize {
+        let before = self.records.len();
+        self.records
+            .retain(|record| record.approximate_age(now).is_none_or(|age| age <= max_age));
+        before - self.records.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stale_records() {
+        let mut event = crate::testing::dynamodb().inserts(4).build();
+        for (record, created) in
+            event
+                .records
+                .iter_mut()
+                .zip(vec![Some(10.0), Some(95.5), None, Some(120.0)])
+        {
+            record.dynamodb.approximate_creation_date_time = created;
+        }
+        let now = UNIX_EPOCH + Duration::from_secs(100);
+        let ages: Vec<_> = event
+            .records
+            .iter()
+            .map(|record| record.approximate_age(now))
+            .collect();
+        assert_eq!(
+            ages,
+            vec![
+                Some(Duration::from_secs(90)),
+                Some(Duration::from_millis(4500)),
+                None,
+                Some(Duration::default())
+            ]
+        );
+
+        let ids: Vec<String> = event.records.iter().map(|r| r.event_id.clone()).collect();
+        assert_eq!(event.drop_older_than_at(Duration::from_secs(60), now), 1);
+        assert_eq!(
+            event
+                .records
+                .iter()
+                .map(|r| r.event_id.clone())
+                .collect::<Vec<_>>(),
+            ids[1..].to_vec()
+        );
+        assert_eq!(event.drop_older_than(Duration::from_secs(60)), 2);
+        assert_eq!(event.records.len(), 1);
+        assert!(event.records[0].dynamodb.created_at().is_none());
+
+        // Valid JSON, but past SystemTime's range.
+        let mut record = crate::testing::dynamodb()
+            .inserts(1)
+            .build()
+            .records
+            .remove(0);
+        record.dynamodb.approximate_creation_date_time = Some(1e19);
+        assert_eq!(record.dynamodb.created_at(), None);
+        assert_eq!(record.approximate_age(now), None);
+    }
+}
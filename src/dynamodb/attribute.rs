@@ -0,0 +1,164 @@
+use super::AttributeValue;
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Constructors for each attribute type.
+impl AttributeValue {
+    pub fn s<S: Into<String>>(value: S) -> AttributeValue {
+        AttributeValue {
+            s: Some(value.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn n<N: ToString>(value: N) -> AttributeValue {
+        AttributeValue {
+            n: Some(value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn bool(value: bool) -> AttributeValue {
+        AttributeValue {
+            bool: Some(value),
+            ..Default::default()
+        }
+    }
+
+    pub fn null() -> AttributeValue {
+        AttributeValue {
+            null: Some(true),
+            ..Default::default()
+        }
+    }
+
+    pub fn b<B: Into<Bytes>>(value: B) -> AttributeValue {
+        AttributeValue {
+            b: Some(value.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn m(value: HashMap<String, AttributeValue>) -> AttributeValue {
+        AttributeValue {
+            m: Some(value),
+            ..Default::default()
+        }
+    }
+
+    pub fn l(value: Vec<AttributeValue>) -> AttributeValue {
+        AttributeValue {
+            l: Some(value),
+            ..Default::default()
+        }
+    }
+
+    pub fn ss<I, S>(values: I) -> AttributeValue
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        AttributeValue {
+            ss: Some(values.into_iter().map(Into::into).collect()),
+            ..Default::default()
+        }
+    }
+
+    pub fn ns<I, N>(values: I) -> AttributeValue
+    where
+        I: IntoIterator<Item = N>,
+        N: ToString,
+    {
+        AttributeValue {
+            ns: Some(values.into_iter().map(|n| n.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    pub fn bs<I, B>(values: I) -> AttributeValue
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Bytes>,
+    {
+        AttributeValue {
+            bs: Some(values.into_iter().map(Into::into).collect()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Accessors returning `None` when the attribute holds a different type.
+impl AttributeValue {
+    pub fn as_s(&self) -> Option<&str> {
+        self.s.as_deref()
+    }
+
+    /// The raw number string, exactly as DynamoDB sent it.
+    pub fn as_n(&self) -> Option<&str> {
+        self.n.as_deref()
+    }
+
+    /// The number parsed into `T`; `None` if not a number or out of range for `T`.
+    pub fn as_n_parsed<T: FromStr>(&self) -> Option<T> {
+        self.as_n()?.parse().ok()
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        self.bool
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.null == Some(true)
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        self.b.as_deref()
+    }
+
+    pub fn as_m(&self) -> Option<&HashMap<String, AttributeValue>> {
+        self.m.as_ref()
+    }
+
+    pub fn as_l(&self) -> Option<&[AttributeValue]> {
+        self.l.as_deref()
+    }
+
+    pub fn as_ss(&self) -> Option<&[String]> {
+        self.ss.as_deref()
+    }
+
+    pub fn as_ns(&self) -> Option<&[String]> {
+        self.ns.as_deref()
+    }
+
+    pub fn as_bs(&self) -> Option<&[Bytes]> {
+        self.bs.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn constructors_and_accessors() {
+        assert_eq!(AttributeValue::s("x").as_s(), Some("x"));
+        assert_eq!(AttributeValue::n(42).as_n(), Some("42"));
+        assert_eq!(AttributeValue::n(42).as_n_parsed::<i64>(), Some(42));
+        assert_eq!(AttributeValue::n("1.5").as_n_parsed::<i64>(), None);
+        assert_eq!(AttributeValue::bool(true).as_bool(), Some(true));
+        assert!(AttributeValue::null().is_null());
+        assert_eq!(AttributeValue::b(&b"abc"[..]).as_bytes(), Some(&b"abc"[..]));
+        assert_eq!(AttributeValue::s("x").as_n(), None);
+        assert_eq!(
+            AttributeValue::ns(vec![1, 2]).as_ns(),
+            Some(&["1".to_string(), "2".to_string()][..])
+        );
+
+        let mut map = HashMap::new();
+        map.insert("Name".to_string(), AttributeValue::s("Joe"));
+        let value = AttributeValue::m(map);
+        assert_eq!(value.as_m().unwrap()["Name"].as_s(), Some("Joe"));
+    }
+}
@@ -0,0 +1,265 @@
+//! `WithBinaryEncoding` for attribute values, which writes `B` and `BS` in
+//! the chosen encoding and passes it down to nested `L` and `M` values, and
+//! for whole events, records and stream records, which pass it down to Keys,
+//! NewImage and OldImage.
+
+use super::{
+    AttributeValue, DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType,
+    DynamoDBStreamRecord, DynamoDBUserIdentity,
+};
+use crate::encoding::base64::{BinaryEncoding, WithBinaryEncoding};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::collections::HashMap;
+
+impl Serialize for WithBinaryEncoding<&AttributeValue> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let WithBinaryEncoding { value, encoding } = *self;
+        // What the derived impl writes.
+        if encoding == BinaryEncoding::Base64 {
+            return value.serialize(serializer);
+        }
+        let len = [
+            value.b.is_some(),
+            value.bool.is_some(),
+            value.bs.is_some(),
+            value.l.is_some(),
+            value.m.is_some(),
+            value.n.is_some(),
+            value.ns.is_some(),
+            value.null.is_some(),
+            value.s.is_some(),
+            value.ss.is_some(),
+        ]
+        .iter()
+        .filter(|set| **set)
+        .count();
+        let mut state = serializer.serialize_struct("AttributeValue", len)?;
+        if let Some(b) = &value.b {
+            state.serialize_field("B", &WithBinaryEncoding::new(b, encoding))?;
+        }
+        if let Some(bool) = &value.bool {
+            state.serialize_field("BOOL", bool)?;
+        }
+        if let Some(bs) = &value.bs {
+            state.serialize_field("BS", &BinarySet(bs, encoding))?;
+        }
+        if let Some(l) = &value.l {
+            state.serialize_field("L", &WithBinaryEncoding::new(&l[..], encoding))?;
+        }
+        if let Some(m) = &value.m {
+            state.serialize_field("M", &WithBinaryEncoding::new(m, encoding))?;
+        }
+        if let Some(n) = &value.n {
+            state.serialize_field("N", n)?;
+        }
+        if let Some(ns) = &value.ns {
+            state.serialize_field("NS", ns)?;
+        }
+        if let Some(null) = &value.null {
+            state.serialize_field("NULL", null)?;
+        }
+        if let Some(s) = &value.s {
+            state.serialize_field("S", s)?;
+        }
+        if let Some(ss) = &value.ss {
+            state.serialize_field("SS", ss)?;
+        }
+        state.end()
+    }
+}
+
+impl Serialize for WithBinaryEncoding<&[AttributeValue]> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoding = self.encoding;
+        serializer.collect_seq(
+            self.value
+                .iter()
+                .map(|value| WithBinaryEncoding::new(value, encoding)),
+        )
+    }
+}
+
+impl Serialize for WithBinaryEncoding<&HashMap<String, AttributeValue>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoding = self.encoding;
+        serializer.collect_map(
+            self.value
+                .iter()
+                .map(|(name, value)| (name, WithBinaryEncoding::new(value, encoding))),
+        )
+    }
+}
+
+struct BinarySet<'a>(&'a [bytes::Bytes], BinaryEncoding);
+
+impl Serialize for BinarySet<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoding = self.1;
+        serializer.collect_seq(
+            self.0
+                .iter()
+                .map(|bytes| WithBinaryEncoding::new(bytes, encoding)),
+        )
+    }
+}
+
+// Mirrors of the event types with the same serde attributes, so the output
+// only differs from the derived impls in how binary values are written.
+#[derive(Serialize)]
+#[serde(rename = "DynamoDBEvent")]
+struct EncodedEvent<'a> {
+    #[serde(rename = "Records")]
+    records: Vec<WithBinaryEncoding<&'a DynamoDBEventRecord>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "DynamoDBEventRecord")]
+struct EncodedEventRecord<'a> {
+    #[serde(rename = "awsRegion")]
+    aws_region: &'a str,
+    #[serde(rename = "dynamodb")]
+    dynamodb: WithBinaryEncoding<&'a DynamoDBStreamRecord>,
+    #[serde(rename = "eventID")]
+    event_id: &'a str,
+    #[serde(rename = "eventName")]
+    event_name: &'a DynamoDBOperationType,
+    #[serde(rename = "eventSource")]
+    event_source: &'a str,
+    #[serde(rename = "eventVersion")]
+    event_version: &'a str,
+    #[serde(rename = "eventSourceARN")]
+    event_source_arn: &'a str,
+    #[serde(rename = "userIdentity")]
+    user_identity: &'a Option<DynamoDBUserIdentity>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    extra: &'a HashMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "DynamoDBStreamRecord")]
+struct EncodedStreamRecord<'a> {
+    #[serde(rename = "ApproximateCreationDateTime")]
+    approximate_creation_date_time: Option<f64>,
+    #[serde(rename = "Keys")]
+    keys: Option<WithBinaryEncoding<&'a HashMap<String, AttributeValue>>>,
+    #[serde(rename = "NewImage")]
+    new_image: Option<WithBinaryEncoding<&'a HashMap<String, AttributeValue>>>,
+    #[serde(rename = "OldImage")]
+    old_image: Option<WithBinaryEncoding<&'a HashMap<String, AttributeValue>>>,
+    #[serde(rename = "SequenceNumber")]
+    sequence_number: &'a str,
+    #[serde(rename = "SizeBytes")]
+    size_bytes: u64,
+    #[serde(rename = "StreamViewType")]
+    stream_view_type: &'a str,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    extra: &'a HashMap<String, serde_json::Value>,
+}
+
+impl Serialize for WithBinaryEncoding<&DynamoDBEvent> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let WithBinaryEncoding { value, encoding } = *self;
+        EncodedEvent {
+            records: value
+                .records
+                .iter()
+                .map(|record| WithBinaryEncoding::new(record, encoding))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Serialize for WithBinaryEncoding<&DynamoDBEventRecord> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let WithBinaryEncoding { value, encoding } = *self;
+        EncodedEventRecord {
+            aws_region: &value.aws_region,
+            dynamodb: WithBinaryEncoding::new(&value.dynamodb, encoding),
+            event_id: &value.event_id,
+            event_name: &value.event_name,
+            event_source: &value.event_source,
+            event_version: &value.event_version,
+            event_source_arn: &value.event_source_arn,
+            user_identity: &value.user_identity,
+            #[cfg(feature = "extra-fields")]
+            extra: &value.extra,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Serialize for WithBinaryEncoding<&DynamoDBStreamRecord> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let WithBinaryEncoding { value, encoding } = *self;
+        EncodedStreamRecord {
+            approximate_creation_date_time: value.approximate_creation_date_time,
+            keys: encoded_item(&value.keys, encoding),
+            new_image: encoded_item(&value.new_image, encoding),
+            old_image: encoded_item(&value.old_image, encoding),
+            sequence_number: &value.sequence_number,
+            size_bytes: value.size_bytes,
+            stream_view_type: &value.stream_view_type,
+            #[cfg(feature = "extra-fields")]
+            extra: &value.extra,
+        }
+        .serialize(serializer)
+    }
+}
+
+fn encoded_item(
+    item: &Option<HashMap<String, AttributeValue>>,
+    encoding: BinaryEncoding,
+) -> Option<WithBinaryEncoding<&HashMap<String, AttributeValue>>> {
+    item.as_ref()
+        .map(|item| WithBinaryEncoding::new(item, encoding))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn raw_bytes_in_events() {
+        let mut event = fixtures::dynamodb::insert_event();
+        let record = &mut event.records[0].dynamodb;
+        record
+            .keys
+            .as_mut()
+            .unwrap()
+            .insert("Avatar".to_string(), AttributeValue::b(&b"\x00png"[..]));
+        record.new_image.as_mut().unwrap().insert(
+            "Thumbnails".to_string(),
+            AttributeValue::bs(vec![&b"ab"[..], &b"c"[..]]),
+        );
+
+        let raw = WithBinaryEncoding::new(&event, BinaryEncoding::Bytes);
+        let msgpack = rmp_serde::to_vec_named(&raw).unwrap();
+        // A bin 8 header, then the four bytes, rather than a base64 string.
+        assert!(msgpack
+            .windows(6)
+            .any(|window| window == b"\xc4\x04\x00png"));
+        assert_eq!(
+            rmp_serde::from_slice::<DynamoDBEvent>(&msgpack).unwrap(),
+            event
+        );
+
+        // Base64 writes what the derived impls do.
+        let base64 = WithBinaryEncoding::new(&event, BinaryEncoding::Base64);
+        assert_eq!(
+            rmp_serde::to_vec_named(&base64).unwrap(),
+            rmp_serde::to_vec_named(&event).unwrap()
+        );
+        let raw_json = serde_json::to_value(raw).unwrap();
+        let mut base64_json = serde_json::to_value(&event).unwrap();
+        let keys = &mut base64_json["Records"][0]["dynamodb"]["Keys"];
+        keys["Avatar"]["B"] = serde_json::json!([0, 112, 110, 103]);
+        base64_json["Records"][0]["dynamodb"]["NewImage"]["Thumbnails"]["BS"] =
+            serde_json::json!([[97, 98], [99]]);
+        assert_eq!(raw_json, base64_json);
+    }
+}
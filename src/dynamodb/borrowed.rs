@@ -0,0 +1,309 @@
+//! Borrowed counterparts of the event types, for parsing large batches
+//! without allocating a `String` per field. Strings borrow from the input
+//! unless they contain escape sequences; binary attributes are base64 on the
+//! wire, so they are always decoded into owned buffers.
+
+use super::{
+    AttributeValue, DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType,
+    DynamoDBStreamRecord, DynamoDBUserIdentity,
+};
+use crate::error::{self, EventParseError};
+use serde::de::{Deserializer, Visitor};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A `DynamoDBEvent` borrowing from the buffer it was parsed from.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct DynamoDBEventRef<'a> {
+    #[serde(borrow, rename = "Records")]
+    pub records: Vec<DynamoDBEventRecordRef<'a>>,
+}
+
+impl DynamoDBEventRef<'_> {
+    pub fn into_owned(self) -> DynamoDBEvent {
+        DynamoDBEvent {
+            records: self
+                .records
+                .into_iter()
+                .map(DynamoDBEventRecordRef::into_owned)
+                .collect(),
+        }
+    }
+}
+
+/// A `DynamoDBEventRecord` borrowing from the buffer it was parsed from.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
+pub struct DynamoDBEventRecordRef<'a> {
+    #[serde(borrow, default, rename = "awsRegion")]
+    pub aws_region: Cow<'a, str>,
+    #[serde(borrow, rename = "dynamodb")]
+    pub dynamodb: DynamoDBStreamRecordRef<'a>,
+    #[serde(borrow, rename = "eventID")]
+    pub event_id: Cow<'a, str>,
+    #[serde(rename = "eventName")]
+    pub event_name: DynamoDBOperationType,
+    #[serde(borrow, default, rename = "eventSource")]
+    pub event_source: Cow<'a, str>,
+    #[serde(borrow, default, rename = "eventVersion")]
+    pub event_version: Cow<'a, str>,
+    #[serde(borrow, default, rename = "eventSourceARN")]
+    pub event_source_arn: Cow<'a, str>,
+    #[serde(rename = "userIdentity")]
+    pub user_identity: Option<DynamoDBUserIdentity>,
+    // Owned, as on DynamoDBEventRecord; fields not modelled here are rare.
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl DynamoDBEventRecordRef<'_> {
+    pub fn into_owned(self) -> DynamoDBEventRecord {
+        DynamoDBEventRecord {
+            aws_region: self.aws_region.into_owned(),
+            dynamodb: self.dynamodb.into_owned(),
+            event_id: self.event_id.into_owned(),
+            event_name: self.event_name,
+            event_source: self.event_source.into_owned(),
+            event_version: self.event_version.into_owned(),
+            event_source_arn: self.event_source_arn.into_owned(),
+            user_identity: self.user_identity,
+            #[cfg(feature = "extra-fields")]
+            extra: self.extra,
+        }
+    }
+}
+
+/// A `DynamoDBStreamRecord` borrowing from the buffer it was parsed from.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
+pub struct DynamoDBStreamRecordRef<'a> {
+    #[serde(rename = "ApproximateCreationDateTime")]
+    pub approximate_creation_date_time: Option<f64>,
+    #[serde(borrow, default, rename = "Keys", deserialize_with = "option_map")]
+    pub keys: Option<HashMap<Cow<'a, str>, AttributeValueRef<'a>>>,
+    #[serde(borrow, default, rename = "NewImage", deserialize_with = "option_map")]
+    pub new_image: Option<HashMap<Cow<'a, str>, AttributeValueRef<'a>>>,
+    #[serde(borrow, default, rename = "OldImage", deserialize_with = "option_map")]
+    pub old_image: Option<HashMap<Cow<'a, str>, AttributeValueRef<'a>>>,
+    #[serde(borrow, rename = "SequenceNumber")]
+    pub sequence_number: Cow<'a, str>,
+    #[serde(default, rename = "SizeBytes")]
+    pub size_bytes: u64,
+    #[serde(borrow, default, rename = "StreamViewType")]
+    pub stream_view_type: Cow<'a, str>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl DynamoDBStreamRecordRef<'_> {
+    pub fn into_owned(self) -> DynamoDBStreamRecord {
+        DynamoDBStreamRecord {
+            approximate_creation_date_time: self.approximate_creation_date_time,
+            keys: self.keys.map(item_into_owned),
+            new_image: self.new_image.map(item_into_owned),
+            old_image: self.old_image.map(item_into_owned),
+            sequence_number: self.sequence_number.into_owned(),
+            size_bytes: self.size_bytes,
+            stream_view_type: self.stream_view_type.into_owned(),
+            #[cfg(feature = "extra-fields")]
+            extra: self.extra,
+        }
+    }
+}
+
+/// An `AttributeValue` borrowing its strings from the buffer it was parsed from.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct AttributeValueRef<'a> {
+    #[serde(rename = "B")]
+    #[serde(default, with = "crate::encoding::base64::option")]
+    pub b: Option<bytes::Bytes>,
+    #[serde(rename = "BOOL")]
+    pub bool: Option<bool>,
+    #[serde(rename = "BS")]
+    #[serde(default, with = "crate::encoding::base64::option_vec")]
+    pub bs: Option<Vec<bytes::Bytes>>,
+    #[serde(borrow, rename = "L")]
+    pub l: Option<Vec<AttributeValueRef<'a>>>,
+    #[serde(borrow, default, rename = "M", deserialize_with = "option_map")]
+    pub m: Option<HashMap<Cow<'a, str>, AttributeValueRef<'a>>>,
+    #[serde(borrow, default, rename = "N", deserialize_with = "option_str")]
+    pub n: Option<Cow<'a, str>>,
+    #[serde(borrow, default, rename = "NS", deserialize_with = "option_vec_str")]
+    pub ns: Option<Vec<Cow<'a, str>>>,
+    #[serde(rename = "NULL")]
+    pub null: Option<bool>,
+    #[serde(borrow, default, rename = "S", deserialize_with = "option_str")]
+    pub s: Option<Cow<'a, str>>,
+    #[serde(borrow, default, rename = "SS", deserialize_with = "option_vec_str")]
+    pub ss: Option<Vec<Cow<'a, str>>>,
+}
+
+impl AttributeValueRef<'_> {
+    pub fn into_owned(self) -> AttributeValue {
+        AttributeValue {
+            b: self.b,
+            bool: self.bool,
+            bs: self.bs,
+            l: self
+                .l
+                .map(|l| l.into_iter().map(AttributeValueRef::into_owned).collect()),
+            m: self.m.map(item_into_owned),
+            n: self.n.map(Cow::into_owned),
+            ns: self.ns.map(strings_into_owned),
+            null: self.null,
+            s: self.s.map(Cow::into_owned),
+            ss: self.ss.map(strings_into_owned),
+        }
+    }
+}
+
+impl DynamoDBEvent {
+    /// Parses an event that borrows its strings from `data`; see `DynamoDBEventRef`.
+    pub fn from_slice_borrowed(data: &[u8]) -> Result<DynamoDBEventRef<'_>, EventParseError> {
+        error::from_slice("dynamodb", data)
+    }
+}
+
+fn item_into_owned(
+    item: HashMap<Cow<'_, str>, AttributeValueRef<'_>>,
+) -> HashMap<String, AttributeValue> {
+    item.into_iter()
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect()
+}
+
+fn strings_into_owned(values: Vec<Cow<'_, str>>) -> Vec<String> {
+    values.into_iter().map(Cow::into_owned).collect()
+}
+
+// serde only borrows a `Cow<str>` when it is the field's whole type, so
+// strings nested in Options, Vecs and map keys go through this wrapper.
+#[derive(PartialEq, Eq, Hash)]
+struct CowStr<'a>(Cow<'a, str>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for CowStr<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<CowStr<'a>, D::Error> {
+        struct CowStrVisitor;
+
+        impl<'de> Visitor<'de> for CowStrVisitor {
+            type Value = CowStr<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E>(self, value: &'de str) -> Result<CowStr<'de>, E> {
+                Ok(CowStr(Cow::Borrowed(value)))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<CowStr<'de>, E> {
+                Ok(CowStr(Cow::Owned(value.to_string())))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<CowStr<'de>, E> {
+                Ok(CowStr(Cow::Owned(value)))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
+
+fn option_str<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<CowStr>::deserialize(deserializer)?.map(|value| value.0))
+}
+
+fn option_vec_str<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Vec<Cow<'a, str>>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Option::<Vec<CowStr>>::deserialize(deserializer)?;
+    Ok(values.map(|values| values.into_iter().map(|value| value.0).collect()))
+}
+
+fn option_map<'de: 'a, 'a, D, V>(
+    deserializer: D,
+) -> Result<Option<HashMap<Cow<'a, str>, V>>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let item = Option::<HashMap<CowStr, V>>::deserialize(deserializer)?;
+    Ok(item.map(|item| {
+        item.into_iter()
+            .map(|(name, value)| (name.0, value))
+            .collect()
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn borrowed_matches_owned() {
+        for data in &[
+            fixtures::dynamodb::INSERT_EVENT_JSON,
+            fixtures::dynamodb::MODIFY_EVENT_JSON,
+            fixtures::dynamodb::REMOVE_EVENT_JSON,
+        ] {
+            let owned: DynamoDBEvent = serde_json::from_slice(data).unwrap();
+            let borrowed = DynamoDBEvent::from_slice_borrowed(data).unwrap();
+            let record = &borrowed.records[0];
+            assert!(matches!(record.event_id, Cow::Borrowed(_)));
+            let keys = record.dynamodb.keys.as_ref().unwrap();
+            assert!(keys.keys().all(|name| matches!(name, Cow::Borrowed(_))));
+            assert_eq!(borrowed.into_owned(), owned);
+        }
+    }
+
+    #[cfg(feature = "extra-fields")]
+    #[test]
+    fn extra_fields_match_owned() {
+        let mut value: serde_json::Value =
+            serde_json::from_slice(fixtures::dynamodb::INSERT_EVENT_JSON).unwrap();
+        value["Records"][0]["recordFormat"] = "application/json".into();
+        value["Records"][0]["dynamodb"]["ApproximateCreationDateTimePrecision"] =
+            "MICROSECOND".into();
+        let data = serde_json::to_vec(&value).unwrap();
+
+        let owned: DynamoDBEvent = serde_json::from_slice(&data).unwrap();
+        let borrowed = DynamoDBEvent::from_slice_borrowed(&data).unwrap();
+        let record = &borrowed.records[0];
+        assert_eq!(record.extra["recordFormat"], "application/json");
+        assert!(matches!(record.event_id, Cow::Borrowed(_)));
+        assert_eq!(
+            record.dynamodb.extra["ApproximateCreationDateTimePrecision"],
+            "MICROSECOND"
+        );
+        assert_eq!(borrowed.into_owned(), owned);
+    }
+
+    #[test]
+    fn escaped_strings_are_owned() {
+        let data = br#"{"S": "a\"b", "SS": ["plain"]}"#;
+        let value: AttributeValueRef = serde_json::from_slice(data).unwrap();
+        assert_eq!(value.s, Some(Cow::Owned::<str>("a\"b".to_string())));
+        assert!(matches!(
+            value.ss.as_ref().unwrap()[0],
+            Cow::Borrowed("plain")
+        ));
+        assert_eq!(value.into_owned().ss, Some(vec!["plain".to_string()]));
+    }
+}
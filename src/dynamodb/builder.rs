@@ -0,0 +1,282 @@
+use super::{
+    item_size, AttributeValue, DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType,
+    DynamoDBStreamRecord, DynamoDBStreamViewType, DynamoDBUserIdentity,
+};
+use crate::platform;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::UNIX_EPOCH;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The StreamViewType of built records unless set. It is not inferred from
+/// the images: in a `NEW_AND_OLD_IMAGES` stream an INSERT has only a NewImage
+/// and a REMOVE only an OldImage, so the images cannot tell the view apart.
+pub const DEFAULT_STREAM_VIEW_TYPE: DynamoDBStreamViewType = DynamoDBStreamViewType::NewAndOldImage;
+
+// A unique 32 character hex id, shaped like the eventIDs AWS generates.
+fn generate_event_id(n: u64) -> String {
+    let mut hasher = DefaultHasher::new();
+    (n, platform::now()).hash(&mut hasher);
+    let high = hasher.finish();
+    n.hash(&mut hasher);
+    format!("{:016x}{:016x}", high, hasher.finish())
+}
+
+fn now() -> f64 {
+    platform::now()
+        .and_then(|now| now.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as f64)
+        .unwrap_or_default()
+}
+
+/// Builds an event from records, typically made with `DynamoDBEventRecordBuilder`.
+#[derive(Debug, Clone, Default)]
+pub struct DynamoDBEventBuilder {
+    records: Vec<DynamoDBEventRecord>,
+}
+
+impl DynamoDBEventBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(mut self, record: DynamoDBEventRecord) -> Self {
+        self.records.push(record);
+        self
+    }
+
+    pub fn records<I: IntoIterator<Item = DynamoDBEventRecord>>(mut self, records: I) -> Self {
+        self.records.extend(records);
+        self
+    }
+
+    pub fn build(self) -> DynamoDBEvent {
+        DynamoDBEvent {
+            records: self.records,
+        }
+    }
+}
+
+/// Builds a single stream record. Anything not set gets a realistic default:
+/// `aws:dynamodb` as the source, version `1.1`, a fresh eventID, a
+/// SequenceNumber greater than any previously built record's, and
+/// `DEFAULT_STREAM_VIEW_TYPE`.
+#[derive(Debug, Clone)]
+pub struct DynamoDBEventRecordBuilder {
+    event_name: DynamoDBOperationType,
+    aws_region: String,
+    account_id: String,
+    table_name: String,
+    stream_label: String,
+    event_id: Option<String>,
+    sequence_number: Option<String>,
+    approximate_creation_date_time: Option<f64>,
+    keys: HashMap<String, AttributeValue>,
+    new_image: Option<HashMap<String, AttributeValue>>,
+    old_image: Option<HashMap<String, AttributeValue>>,
+    stream_view_type: DynamoDBStreamViewType,
+    user_identity: Option<DynamoDBUserIdentity>,
+}
+
+impl Default for DynamoDBEventRecordBuilder {
+    fn default() -> Self {
+        DynamoDBEventRecordBuilder {
+            event_name: DynamoDBOperationType::Insert,
+            aws_region: "us-east-1".to_string(),
+            account_id: "123456789012".to_string(),
+            table_name: "Table".to_string(),
+            stream_label: "2016-11-16T20:42:48.104".to_string(),
+            event_id: None,
+            sequence_number: None,
+            approximate_creation_date_time: None,
+            keys: HashMap::new(),
+            new_image: None,
+            old_image: None,
+            stream_view_type: DEFAULT_STREAM_VIEW_TYPE,
+            user_identity: None,
+        }
+    }
+}
+
+impl DynamoDBEventRecordBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn event_name(mut self, event_name: DynamoDBOperationType) -> Self {
+        self.event_name = event_name;
+        self
+    }
+
+    pub fn aws_region<S: Into<String>>(mut self, aws_region: S) -> Self {
+        self.aws_region = aws_region.into();
+        self
+    }
+
+    pub fn account_id<S: Into<String>>(mut self, account_id: S) -> Self {
+        self.account_id = account_id.into();
+        self
+    }
+
+    pub fn table_name<S: Into<String>>(mut self, table_name: S) -> Self {
+        self.table_name = table_name.into();
+        self
+    }
+
+    pub fn stream_label<S: Into<String>>(mut self, stream_label: S) -> Self {
+        self.stream_label = stream_label.into();
+        self
+    }
+
+    pub fn event_id<S: Into<String>>(mut self, event_id: S) -> Self {
+        self.event_id = Some(event_id.into());
+        self
+    }
+
+    pub fn sequence_number<S: Into<String>>(mut self, sequence_number: S) -> Self {
+        self.sequence_number = Some(sequence_number.into());
+        self
+    }
+
+    pub fn approximate_creation_date_time(mut self, epoch_seconds: f64) -> Self {
+        self.approximate_creation_date_time = Some(epoch_seconds);
+        self
+    }
+
+    pub fn key<S: Into<String>>(mut self, name: S, value: AttributeValue) -> Self {
+        self.keys.insert(name.into(), value);
+        self
+    }
+
+    pub fn new_image(mut self, image: HashMap<String, AttributeValue>) -> Self {
+        self.new_image = Some(image);
+        self
+    }
+
+    pub fn old_image(mut self, image: HashMap<String, AttributeValue>) -> Self {
+        self.old_image = Some(image);
+        self
+    }
+
+    // Defaults to DEFAULT_STREAM_VIEW_TYPE, whichever images are set.
+    pub fn stream_view_type(mut self, stream_view_type: DynamoDBStreamViewType) -> Self {
+        self.stream_view_type = stream_view_type;
+        self
+    }
+
+    pub fn user_identity(mut self, user_identity: DynamoDBUserIdentity) -> Self {
+        self.user_identity = Some(user_identity);
+        self
+    }
+
+    pub fn build(self) -> DynamoDBEventRecord {
+        let n = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let size_bytes = [
+            Some(&self.keys),
+            self.new_image.as_ref(),
+            self.old_image.as_ref(),
+        ]
+        .iter()
+        .flatten()
+        .map(|item| item_size(item) as u64)
+        .sum();
+        DynamoDBEventRecord {
+            aws_region: self.aws_region.clone(),
+            dynamodb: DynamoDBStreamRecord {
+                approximate_creation_date_time: self
+                    .approximate_creation_date_time
+                    .or_else(|| Some(now())),
+                keys: Some(self.keys),
+                new_image: self.new_image,
+                old_image: self.old_image,
+                sequence_number: self
+                    .sequence_number
+                    .unwrap_or_else(|| format!("1{:025}", n)),
+                size_bytes,
+                stream_view_type: self.stream_view_type.to_string(),
+                #[cfg(feature = "extra-fields")]
+                extra: HashMap::new(),
+            },
+            event_id: self.event_id.unwrap_or_else(|| generate_event_id(n)),
+            event_name: self.event_name,
+            event_source: "aws:dynamodb".to_string(),
+            event_version: "1.1".to_string(),
+            event_source_arn: format!(
+                "arn:aws:dynamodb:{}:{}:table/{}/stream/{}",
+                self.aws_region, self.account_id, self.table_name, self.stream_label
+            ),
+            user_identity: self.user_identity,
+            #[cfg(feature = "extra-fields")]
+            extra: HashMap::new(),
+        }
+    }
+}
+
+impl DynamoDBEvent {
+    pub fn builder() -> DynamoDBEventBuilder {
+        DynamoDBEventBuilder::new()
+    }
+}
+
+impl DynamoDBEventRecord {
+    pub fn builder() -> DynamoDBEventRecordBuilder {
+        DynamoDBEventRecordBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dynamodb::compare_sequence_numbers;
+
+    #[test]
+    fn build_event() {
+        let mut image = HashMap::new();
+        image.insert("Id".to_string(), AttributeValue::s("1"));
+        let event = DynamoDBEvent::builder()
+            .record(
+                DynamoDBEventRecord::builder()
+                    .table_name("Users")
+                    .key("Id", AttributeValue::s("1"))
+                    .new_image(image.clone())
+                    .build(),
+            )
+            .record(
+                DynamoDBEventRecord::builder()
+                    .event_name(DynamoDBOperationType::Remove)
+                    .table_name("Users")
+                    .key("Id", AttributeValue::s("1"))
+                    .old_image(image)
+                    .build(),
+            )
+            .build();
+
+        let (insert, remove) = (&event.records[0], &event.records[1]);
+        assert_eq!(insert.table_name(), Some("Users"));
+        assert_eq!(insert.event_source, "aws:dynamodb");
+        assert_eq!(insert.dynamodb.stream_view_type, "NEW_AND_OLD_IMAGES");
+        assert_eq!(remove.dynamodb.stream_view_type, "NEW_AND_OLD_IMAGES");
+        let keys_only = DynamoDBEventRecord::builder()
+            .stream_view_type(DynamoDBStreamViewType::KeysOnly)
+            .build();
+        assert_eq!(keys_only.dynamodb.stream_view_type, "KEYS_ONLY");
+        assert_ne!(insert.event_id, remove.event_id);
+        assert_eq!(insert.event_id.len(), 32);
+        assert_eq!(
+            compare_sequence_numbers(
+                &insert.dynamodb.sequence_number,
+                &remove.dynamodb.sequence_number
+            ),
+            std::cmp::Ordering::Less
+        );
+
+        let json = serde_json::to_vec(&event).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<DynamoDBEvent>(&json).unwrap(),
+            event
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use super::AttributeValue;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// A single step into a nested attribute: a map key or a list index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PathSegment {
+    Attribute(String),
+    Index(usize),
+}
+
+/// The location of an attribute inside an item, e.g. `user.address[0].city`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AttributePath(pub Vec<PathSegment>);
+
+impl AttributePath {
+    pub(crate) fn child(&self, segment: PathSegment) -> AttributePath {
+        let mut segments = self.0.clone();
+        segments.push(segment);
+        AttributePath(segments)
+    }
+}
+
+// Escapes names the way `FromStr` expects, so printed paths parse back.
+impl fmt::Display for AttributePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Attribute(name) => {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+                    for c in name.chars() {
+                        if matches!(c, '.' | '[' | ']' | '\\') {
+                            f.write_str("\\")?;
+                        }
+                        write!(f, "{}", c)?;
+                    }
+                }
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An attribute present in both images whose value differs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeChange {
+    pub path: AttributePath,
+    pub old: AttributeValue,
+    pub new: AttributeValue,
+}
+
+/// The difference between the old and new image of a stream record.
+///
+/// Nested maps and lists are compared element-wise, so a change deep inside
+/// an item is reported at its own path rather than as a change of the whole
+/// top-level attribute.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageDiff {
+    pub added: Vec<(AttributePath, AttributeValue)>,
+    pub removed: Vec<(AttributePath, AttributeValue)>,
+    pub changed: Vec<AttributeChange>,
+}
+
+impl ImageDiff {
+    /// Computes the difference between two items.
+    pub fn between(
+        old: &HashMap<String, AttributeValue>,
+        new: &HashMap<String, AttributeValue>,
+    ) -> ImageDiff {
+        let mut diff = ImageDiff::default();
+        diff.diff_maps(&AttributePath::default(), old, new);
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    fn diff_maps(
+        &mut self,
+        path: &AttributePath,
+        old: &HashMap<String, AttributeValue>,
+        new: &HashMap<String, AttributeValue>,
+    ) {
+        let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+        for name in names {
+            let path = path.child(PathSegment::Attribute(name.clone()));
+            match (old.get(name), new.get(name)) {
+                (Some(old), Some(new)) => self.diff_values(path, old, new),
+                (Some(old), None) => self.removed.push((path, old.clone())),
+                (None, Some(new)) => self.added.push((path, new.clone())),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn diff_values(&mut self, path: AttributePath, old: &AttributeValue, new: &AttributeValue) {
+        if old == new {
+            return;
+        }
+        match (old, new) {
+            (
+                AttributeValue {
+                    m: Some(old_map), ..
+                },
+                AttributeValue {
+                    m: Some(new_map), ..
+                },
+            ) => self.diff_maps(&path, old_map, new_map),
+            (
+                AttributeValue {
+                    l: Some(old_list), ..
+                },
+                AttributeValue {
+                    l: Some(new_list), ..
+                },
+            ) => {
+                for index in 0..old_list.len().max(new_list.len()) {
+                    let path = path.child(PathSegment::Index(index));
+                    match (old_list.get(index), new_list.get(index)) {
+                        (Some(old), Some(new)) => self.diff_values(path, old, new),
+                        (Some(old), None) => self.removed.push((path, old.clone())),
+                        (None, Some(new)) => self.added.push((path, new.clone())),
+                        (None, None) => unreachable!(),
+                    }
+                }
+            }
+            _ => self.changed.push(AttributeChange {
+                path,
+                old: old.clone(),
+                new: new.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn item(value: serde_json::Value) -> HashMap<String, AttributeValue> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn nested_diff() {
+        let old = item(json!({
+            "Id": {"S": "1"},
+            "Gone": {"BOOL": true},
+            "User": {"M": {
+                "Name": {"S": "Joe"},
+                "Tags": {"L": [{"S": "a"}, {"S": "b"}]}
+            }}
+        }));
+        let new = item(json!({
+            "Id": {"S": "1"},
+            "New": {"N": "3"},
+            "User": {"M": {
+                "Name": {"S": "Jim"},
+                "Tags": {"L": [{"S": "a"}]}
+            }}
+        }));
+        let diff = ImageDiff::between(&old, &new);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].0.to_string(), "New");
+        let removed: Vec<String> = diff.removed.iter().map(|(p, _)| p.to_string()).collect();
+        assert_eq!(removed, vec!["Gone", "User.Tags[1]"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].path.to_string(), "User.Name");
+        assert_eq!(diff.changed[0].new.s.as_deref(), Some("Jim"));
+        assert!(ImageDiff::between(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use super::{DynamoDBKeyType, DynamoDBOperationType, DynamoDBStreamViewType};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Returned when parsing a string that is not one of an enum's known wire values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseEnumError {
+    type_name: &'static str,
+    value: String,
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown {} {:?}", self.type_name, self.value)
+    }
+}
+
+impl Error for ParseEnumError {}
+
+// Maps each known variant to its wire string, with anything else carried in
+// the `Unknown` variant, and (de)serializes through those strings. Display and
+// FromStr use the same strings, but FromStr rejects unknown values since it is
+// meant for parsing configuration rather than events.
+macro_rules! wire_enum {
+    ($name:ident { $($variant:ident => $wire:literal,)* }) => {
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $wire,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+
+            fn from_wire(value: &str) -> $name {
+                match value {
+                    $($wire => $name::$variant,)*
+                    _ => $name::Unknown(value.to_string()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseEnumError;
+
+            fn from_str(s: &str) -> Result<$name, ParseEnumError> {
+                match $name::from_wire(s) {
+                    $name::Unknown(value) => Err(ParseEnumError {
+                        type_name: stringify!($name),
+                        value,
+                    }),
+                    known => Ok(known),
+                }
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$name, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok($name::from_wire(&value))
+            }
+        }
+    };
+}
+
+wire_enum!(DynamoDBKeyType {
+    Hash => "HASH",
+    Range => "RANGE",
+});
+
+wire_enum!(DynamoDBOperationType {
+    Insert => "INSERT",
+    Modify => "MODIFY",
+    Remove => "REMOVE",
+});
+
+wire_enum!(DynamoDBStreamViewType {
+    NewImage => "NEW_IMAGE",
+    OldImage => "OLD_IMAGE",
+    NewAndOldImage => "NEW_AND_OLD_IMAGES",
+    KeysOnly => "KEYS_ONLY",
+});
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unknown_values_are_kept() {
+        let known: DynamoDBOperationType = serde_json::from_str(r#""MODIFY""#).unwrap();
+        assert_eq!(known, DynamoDBOperationType::Modify);
+        let unknown: DynamoDBOperationType = serde_json::from_str(r#""UPSERT""#).unwrap();
+        assert_eq!(
+            unknown,
+            DynamoDBOperationType::Unknown("UPSERT".to_string())
+        );
+        assert_eq!(serde_json::to_string(&unknown).unwrap(), r#""UPSERT""#);
+
+        let view: DynamoDBStreamViewType = serde_json::from_str(r#""KEYS_ONLY""#).unwrap();
+        assert_eq!(view.as_str(), "KEYS_ONLY");
+        let key: DynamoDBKeyType = serde_json::from_str(r#""RANGE""#).unwrap();
+        assert_eq!(key, DynamoDBKeyType::Range);
+    }
+
+    #[test]
+    fn display_and_from_str() {
+        assert_eq!(DynamoDBOperationType::Insert.to_string(), "INSERT");
+        assert_eq!(DynamoDBStreamViewType::KeysOnly.to_string(), "KEYS_ONLY");
+        assert_eq!("HASH".parse::<DynamoDBKeyType>(), Ok(DynamoDBKeyType::Hash));
+        assert_eq!(
+            "insert"
+                .parse::<DynamoDBOperationType>()
+                .unwrap_err()
+                .to_string(),
+            r#"unknown DynamoDBOperationType "insert""#
+        );
+    }
+
+    #[test]
+    fn fixture_stream_view_types() {
+        for event in &[
+            crate::fixtures::dynamodb::modify_event(),
+            crate::fixtures::dynamodb::remove_event(),
+        ] {
+            let wire = &event.records[0].dynamodb.stream_view_type;
+            assert_eq!(wire, "NEW_AND_OLD_IMAGES");
+            let view: DynamoDBStreamViewType = wire.parse().unwrap();
+            assert_eq!(view, DynamoDBStreamViewType::NewAndOldImage);
+            assert_eq!(view.to_string(), *wire);
+            let json = serde_json::to_string(wire).unwrap();
+            assert_eq!(
+                serde_json::from_str::<DynamoDBStreamViewType>(&json).unwrap(),
+                view
+            );
+        }
+    }
+}
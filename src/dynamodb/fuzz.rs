@@ -0,0 +1,205 @@
+//! `Arbitrary` implementations producing structurally valid events: every
+//! attribute value has exactly one type, sets are non-empty and unique,
+//! numbers are numeric with at most 38 significant digits, and keys have one
+//! or two non-empty S, N or B attributes.
+
+use super::{
+    AttributeValue, DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType, DynamoDBUserIdentity,
+};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::collections::{BTreeSet, HashMap};
+
+// Bounds the nesting of generated L and M values.
+const MAX_DEPTH: usize = 3;
+
+const REGIONS: &[&str] = &["us-east-1", "us-west-2", "eu-west-1", "ap-southeast-2"];
+
+// An integer or decimal with at most 19 significant digits, well within
+// DynamoDB's 38: the digits of an i64 with a decimal point inserted.
+fn number(u: &mut Unstructured<'_>) -> Result<String> {
+    let mantissa = i64::arbitrary(u)?;
+    let scale: usize = u.int_in_range(0..=18)?;
+    let digits = format!("{:0>width$}", mantissa.unsigned_abs(), width = scale + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - scale);
+    let sign = if mantissa < 0 { "-" } else { "" };
+    Ok(if scale == 0 {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    })
+}
+
+fn non_empty_string(u: &mut Unstructured<'_>) -> Result<String> {
+    let s = String::arbitrary(u)?;
+    Ok(if s.is_empty() { "0".to_string() } else { s })
+}
+
+// A key attribute, which can only be a non-empty S, N or B.
+fn key_value(u: &mut Unstructured<'_>) -> Result<AttributeValue> {
+    Ok(match u.choose_index(3)? {
+        0 => AttributeValue::s(non_empty_string(u)?),
+        1 => AttributeValue::n(number(u)?),
+        _ => {
+            let mut bytes = Vec::<u8>::arbitrary(u)?;
+            if bytes.is_empty() {
+                bytes.push(0);
+            }
+            AttributeValue::b(bytes)
+        }
+    })
+}
+
+fn non_empty_set<'a, T, F>(u: &mut Unstructured<'a>, mut element: F) -> Result<Vec<T>>
+where
+    T: Ord,
+    F: FnMut(&mut Unstructured<'a>) -> Result<T>,
+{
+    let mut set = BTreeSet::new();
+    set.insert(element(u)?);
+    for _ in 0..u.arbitrary_len::<u8>()? {
+        set.insert(element(u)?);
+    }
+    Ok(set.into_iter().collect())
+}
+
+fn attribute_value(u: &mut Unstructured<'_>, depth: usize) -> Result<AttributeValue> {
+    let kinds = if depth < MAX_DEPTH { 10 } else { 8 };
+    Ok(match u.choose_index(kinds)? {
+        0 => AttributeValue::s(String::arbitrary(u)?),
+        1 => AttributeValue::n(number(u)?),
+        2 => AttributeValue::bool(u.arbitrary()?),
+        3 => AttributeValue::null(),
+        4 => AttributeValue::b(Vec::<u8>::arbitrary(u)?),
+        5 => AttributeValue::ss(non_empty_set(u, String::arbitrary)?),
+        6 => AttributeValue::ns(non_empty_set(u, |u| Ok(i64::arbitrary(u)?.to_string()))?),
+        7 => AttributeValue::bs(non_empty_set(u, Vec::<u8>::arbitrary)?),
+        8 => {
+            let len = u.arbitrary_len::<u8>()?;
+            let mut list = Vec::with_capacity(len);
+            for _ in 0..len {
+                list.push(attribute_value(u, depth + 1)?);
+            }
+            AttributeValue::l(list)
+        }
+        _ => AttributeValue::m(item(u, depth + 1)?),
+    })
+}
+
+fn item(u: &mut Unstructured<'_>, depth: usize) -> Result<HashMap<String, AttributeValue>> {
+    let mut item = HashMap::new();
+    for _ in 0..u.arbitrary_len::<u8>()? {
+        item.insert(String::arbitrary(u)?, attribute_value(u, depth)?);
+    }
+    Ok(item)
+}
+
+impl<'a> Arbitrary<'a> for AttributeValue {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        attribute_value(u, 0)
+    }
+}
+
+impl<'a> Arbitrary<'a> for DynamoDBOperationType {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(u.choose(&[
+            DynamoDBOperationType::Insert,
+            DynamoDBOperationType::Modify,
+            DynamoDBOperationType::Remove,
+        ])?
+        .clone())
+    }
+}
+
+impl<'a> Arbitrary<'a> for DynamoDBEventRecord {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let event_name = DynamoDBOperationType::arbitrary(u)?;
+        let mut builder = DynamoDBEventRecord::builder()
+            .event_name(event_name.clone())
+            .aws_region(*u.choose(REGIONS)?)
+            .table_name(format!("Table{}", u8::arbitrary(u)?))
+            .event_id(format!("{:032x}", u128::arbitrary(u)?))
+            .sequence_number(u64::arbitrary(u)?.to_string())
+            .approximate_creation_date_time(f64::from(u32::arbitrary(u)?));
+        let partition_key = non_empty_string(u)?;
+        builder = builder.key(partition_key.clone(), key_value(u)?);
+        if u.arbitrary()? {
+            let sort_key = non_empty_string(u)?;
+            if sort_key != partition_key {
+                builder = builder.key(sort_key, key_value(u)?);
+            }
+        }
+        if event_name != DynamoDBOperationType::Remove {
+            builder = builder.new_image(item(u, 0)?);
+        }
+        if event_name != DynamoDBOperationType::Insert {
+            builder = builder.old_image(item(u, 0)?);
+        }
+        if event_name == DynamoDBOperationType::Remove && u.arbitrary()? {
+            builder = builder.user_identity(DynamoDBUserIdentity::Service {
+                principal_id: "dynamodb.amazonaws.com".to_string(),
+            });
+        }
+        Ok(builder.build())
+    }
+}
+
+impl<'a> Arbitrary<'a> for DynamoDBEvent {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(DynamoDBEvent {
+            records: u.arbitrary()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check_numbers(value: &AttributeValue) {
+        if let Some(n) = value.as_n() {
+            let digits = n.chars().filter(char::is_ascii_digit).count();
+            assert!(digits <= 38, "{}", n);
+            assert!(n.parse::<f64>().is_ok(), "{}", n);
+        }
+        for value in value.as_l().into_iter().flatten() {
+            check_numbers(value);
+        }
+        for value in value.as_m().into_iter().flat_map(|m| m.values()) {
+            check_numbers(value);
+        }
+    }
+
+    #[test]
+    fn arbitrary_events_are_valid() {
+        for seed in 1..64u32 {
+            let data: Vec<u8> = (0..4096u32)
+                .map(|i| (i.wrapping_mul(7919).wrapping_add(seed * 104729) % 251) as u8)
+                .collect();
+            let mut u = Unstructured::new(&data);
+            let event = DynamoDBEvent::arbitrary(&mut u).unwrap();
+            assert!(event.validate().is_ok());
+            let json = serde_json::to_vec(&event).unwrap();
+            assert_eq!(
+                serde_json::from_slice::<DynamoDBEvent>(&json).unwrap(),
+                event
+            );
+            for record in &event.records {
+                let keys = record.dynamodb.keys.as_ref().unwrap();
+                assert!((1..=2).contains(&keys.len()));
+                for value in keys.values() {
+                    let non_empty = value
+                        .as_s()
+                        .map(|s| !s.is_empty())
+                        .or_else(|| value.as_n().map(|_| true))
+                        .or_else(|| value.as_bytes().map(|b| !b.is_empty()));
+                    assert_eq!(non_empty, Some(true), "{:?}", value);
+                }
+                for image in [&record.dynamodb.new_image, &record.dynamodb.old_image] {
+                    for value in image.iter().flat_map(|image| image.values()) {
+                        check_numbers(value);
+                    }
+                }
+            }
+        }
+    }
+}
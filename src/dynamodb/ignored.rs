@@ -0,0 +1,418 @@
+//! A `Deserializer` wrapper that records, in the same pass, the fields the
+//! wrapped `Deserialize` impls skip, in the style of the `serde_ignored`
+//! crate. Derived and hand-written impls alike skip an unknown field through
+//! `deserialize_ignored_any`, which is where it is recorded.
+
+use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, Visitor};
+use std::cell::RefCell;
+use std::fmt;
+
+/// Where the value being deserialized is, e.g. `Records[0].dynamodb`.
+pub(super) enum Path<'a> {
+    Root,
+    Field { parent: &'a Path<'a>, name: String },
+    Index { parent: &'a Path<'a>, index: usize },
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Path::Root => Ok(()),
+            Path::Field {
+                parent: Path::Root,
+                name,
+            } => f.write_str(name),
+            Path::Field { parent, name } => write!(f, "{}.{}", parent, name),
+            Path::Index { parent, index } => write!(f, "{}[{}]", parent, index),
+        }
+    }
+}
+
+/// What was found while deserializing, as paths in input order.
+#[derive(Debug, Default)]
+pub(super) struct Ignored {
+    /// Fields skipped because no impl knew them.
+    pub unknown: Vec<String>,
+    /// Fields set to null, which an `Option` takes as unset.
+    pub nulls: Vec<String>,
+}
+
+/// Wraps a deserializer, or a visitor or seed of one, so everything it
+/// deserializes is tracked at `path`.
+pub(super) struct Wrap<'a, T> {
+    pub inner: T,
+    pub path: &'a Path<'a>,
+    pub ignored: &'a RefCell<Ignored>,
+}
+
+impl<'a, T> Wrap<'a, T> {
+    fn split<U>(self, other: U) -> (T, Wrap<'a, U>) {
+        (
+            self.inner,
+            Wrap {
+                inner: other,
+                path: self.path,
+                ignored: self.ignored,
+            },
+        )
+    }
+}
+
+// The deserializer methods that pass a wrapped visitor on to `inner`.
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                let (inner, visitor) = self.split(visitor);
+                inner.$method($($arg,)* visitor)
+            }
+        )*
+
+        fn is_human_readable(&self) -> bool {
+            self.inner.is_human_readable()
+        }
+    };
+}
+
+macro_rules! forward_deserialize_all {
+    () => {
+        forward_deserialize! {
+            deserialize_any();
+            deserialize_bool();
+            deserialize_i8();
+            deserialize_i16();
+            deserialize_i32();
+            deserialize_i64();
+            deserialize_i128();
+            deserialize_u8();
+            deserialize_u16();
+            deserialize_u32();
+            deserialize_u64();
+            deserialize_u128();
+            deserialize_f32();
+            deserialize_f64();
+            deserialize_char();
+            deserialize_str();
+            deserialize_string();
+            deserialize_bytes();
+            deserialize_byte_buf();
+            deserialize_option();
+            deserialize_unit();
+            deserialize_unit_struct(name: &'static str);
+            deserialize_newtype_struct(name: &'static str);
+            deserialize_seq();
+            deserialize_tuple(len: usize);
+            deserialize_tuple_struct(name: &'static str, len: usize);
+            deserialize_map();
+            deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+            deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+            deserialize_identifier();
+        }
+    };
+}
+
+// The visitor methods that pass a value straight on to `inner`.
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E: de::Error>(self, value: $ty) -> Result<Self::Value, E> {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Wrap<'_, D> {
+    type Error = D::Error;
+
+    forward_deserialize_all!();
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.ignored
+            .borrow_mut()
+            .unknown
+            .push(self.path.to_string());
+        self.inner.deserialize_ignored_any(visitor)
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Wrap<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.record_null();
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.record_null();
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        let (inner, deserializer) = self.split(deserializer);
+        inner.visit_some(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        let (inner, deserializer) = self.split(deserializer);
+        inner.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_seq(WrapSeq {
+            inner: seq,
+            path: self.path,
+            ignored: self.ignored,
+            index: 0,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_map(WrapMap {
+            inner: map,
+            path: self.path,
+            ignored: self.ignored,
+            key: None,
+        })
+    }
+
+    // None of the event types are externally tagged enums, so their contents
+    // are not tracked.
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_enum(data)
+    }
+}
+
+impl<V> Wrap<'_, V> {
+    fn record_null(&self) {
+        if let Path::Field { .. } = self.path {
+            self.ignored.borrow_mut().nulls.push(self.path.to_string());
+        }
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for Wrap<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        let (inner, deserializer) = self.split(deserializer);
+        inner.deserialize(deserializer)
+    }
+}
+
+struct WrapSeq<'a, A> {
+    inner: A,
+    path: &'a Path<'a>,
+    ignored: &'a RefCell<Ignored>,
+    index: usize,
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for WrapSeq<'_, A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        let path = Path::Index {
+            parent: self.path,
+            index: self.index,
+        };
+        self.index += 1;
+        self.inner.next_element_seed(Wrap {
+            inner: seed,
+            path: &path,
+            ignored: self.ignored,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+struct WrapMap<'a, A> {
+    inner: A,
+    path: &'a Path<'a>,
+    ignored: &'a RefCell<Ignored>,
+    // The key of the value about to be deserialized.
+    key: Option<String>,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for WrapMap<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        self.inner.next_key_seed(CaptureKey {
+            inner: seed,
+            key: &mut self.key,
+        })
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, A::Error> {
+        let path = Path::Field {
+            parent: self.path,
+            name: self.key.take().unwrap_or_else(|| "?".to_string()),
+        };
+        self.inner.next_value_seed(Wrap {
+            inner: seed,
+            path: &path,
+            ignored: self.ignored,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+// Wraps a map key's seed, deserializer and visitor to keep a copy of the key
+// for the path of its value.
+struct CaptureKey<'k, T> {
+    inner: T,
+    key: &'k mut Option<String>,
+}
+
+impl<'k, T> CaptureKey<'k, T> {
+    fn split<U>(self, other: U) -> (T, CaptureKey<'k, U>) {
+        (
+            self.inner,
+            CaptureKey {
+                inner: other,
+                key: self.key,
+            },
+        )
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for CaptureKey<'_, S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<S::Value, D::Error> {
+        let (inner, deserializer) = self.split(deserializer);
+        inner.deserialize(deserializer)
+    }
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for CaptureKey<'_, D> {
+    type Error = D::Error;
+
+    forward_deserialize_all!();
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        let (inner, visitor) = self.split(visitor);
+        inner.deserialize_ignored_any(visitor)
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for CaptureKey<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.expecting(f)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<V::Value, E> {
+        *self.key = Some(value.to_string());
+        self.inner.visit_str(value)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<V::Value, E> {
+        *self.key = Some(value.to_string());
+        self.inner.visit_borrowed_str(value)
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<V::Value, E> {
+        *self.key = Some(value.clone());
+        self.inner.visit_string(value)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<V::Value, E> {
+        self.inner.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        self.inner.visit_some(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<V::Value, D::Error> {
+        self.inner.visit_newtype_struct(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_map(map)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<V::Value, A::Error> {
+        self.inner.visit_enum(data)
+    }
+}
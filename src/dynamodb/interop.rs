@@ -0,0 +1,279 @@
+//! Conversions to and from the DynamoDB types of the `aws_lambda_events`
+//! crate, for projects using both crates at module boundaries.
+//!
+//! Converting from `aws_lambda_events` cannot fail. Converting to it can,
+//! since its `ApproximateCreationDateTime` is required and its attribute
+//! values must have a type. Fields only one side models are dropped:
+//! `recordFormat` and `tableName` on theirs, and `extra` on ours.
+
+use super::{
+    AttributeValue, DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType,
+    DynamoDBStreamRecord, DynamoDBStreamViewType, DynamoDBUserIdentity,
+};
+use crate::time::{from_float_epoch, to_float_epoch};
+use aws_lambda_events::dynamodb::{Event, EventRecord, StreamRecord, StreamViewType, UserIdentity};
+use serde_dynamo::{AttributeValue as DynamoAttributeValue, Item};
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::fmt;
+
+/// Returned when an event has no equivalent in `aws_lambda_events`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InteropError {
+    /// An attribute value with no type field set.
+    NoAttributeType,
+    /// A stream record without `ApproximateCreationDateTime`, or with one
+    /// outside chrono's range.
+    NoCreationTime,
+}
+
+impl fmt::Display for InteropError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InteropError::NoAttributeType => f.write_str("attribute value has no known type"),
+            InteropError::NoCreationTime => {
+                f.write_str("stream record has no valid ApproximateCreationDateTime")
+            }
+        }
+    }
+}
+
+impl Error for InteropError {}
+
+impl TryFrom<AttributeValue> for DynamoAttributeValue {
+    type Error = InteropError;
+
+    fn try_from(value: AttributeValue) -> Result<Self, Self::Error> {
+        Ok(if let Some(s) = value.s {
+            DynamoAttributeValue::S(s)
+        } else if let Some(n) = value.n {
+            DynamoAttributeValue::N(n)
+        } else if let Some(b) = value.bool {
+            DynamoAttributeValue::Bool(b)
+        } else if let Some(null) = value.null {
+            DynamoAttributeValue::Null(null)
+        } else if let Some(b) = value.b {
+            DynamoAttributeValue::B(b.to_vec())
+        } else if let Some(m) = value.m {
+            DynamoAttributeValue::M(to_dynamo_map(m)?)
+        } else if let Some(l) = value.l {
+            DynamoAttributeValue::L(
+                l.into_iter()
+                    .map(DynamoAttributeValue::try_from)
+                    .collect::<Result<_, _>>()?,
+            )
+        } else if let Some(ss) = value.ss {
+            DynamoAttributeValue::Ss(ss)
+        } else if let Some(ns) = value.ns {
+            DynamoAttributeValue::Ns(ns)
+        } else if let Some(bs) = value.bs {
+            DynamoAttributeValue::Bs(bs.iter().map(|b| b.to_vec()).collect())
+        } else {
+            return Err(InteropError::NoAttributeType);
+        })
+    }
+}
+
+impl From<DynamoAttributeValue> for AttributeValue {
+    fn from(value: DynamoAttributeValue) -> Self {
+        match value {
+            DynamoAttributeValue::S(s) => AttributeValue::s(s),
+            DynamoAttributeValue::N(n) => AttributeValue::n(n),
+            DynamoAttributeValue::Bool(b) => AttributeValue::bool(b),
+            DynamoAttributeValue::Null(null) => AttributeValue {
+                null: Some(null),
+                ..Default::default()
+            },
+            DynamoAttributeValue::B(b) => AttributeValue::b(b),
+            DynamoAttributeValue::M(m) => AttributeValue::m(from_dynamo_map(m)),
+            DynamoAttributeValue::L(l) => {
+                AttributeValue::l(l.into_iter().map(Into::into).collect())
+            }
+            DynamoAttributeValue::Ss(ss) => AttributeValue::ss(ss),
+            DynamoAttributeValue::Ns(ns) => AttributeValue::ns(ns),
+            DynamoAttributeValue::Bs(bs) => AttributeValue::bs(bs),
+        }
+    }
+}
+
+fn to_dynamo_map(
+    item: HashMap<String, AttributeValue>,
+) -> Result<HashMap<String, DynamoAttributeValue>, InteropError> {
+    item.into_iter()
+        .map(|(name, value)| Ok((name, value.try_into()?)))
+        .collect()
+}
+
+fn from_dynamo_map(item: HashMap<String, DynamoAttributeValue>) -> HashMap<String, AttributeValue> {
+    item.into_iter()
+        .map(|(name, value)| (name, value.into()))
+        .collect()
+}
+
+// Missing Keys or images are an empty item on their side.
+fn to_item(item: Option<HashMap<String, AttributeValue>>) -> Result<Item, InteropError> {
+    Ok(to_dynamo_map(item.unwrap_or_default())?.into())
+}
+
+fn from_item(item: Item) -> Option<HashMap<String, AttributeValue>> {
+    Some(from_dynamo_map(item.into_inner())).filter(|item| !item.is_empty())
+}
+
+impl TryFrom<DynamoDBStreamRecord> for StreamRecord {
+    type Error = InteropError;
+
+    fn try_from(record: DynamoDBStreamRecord) -> Result<Self, Self::Error> {
+        let stream_view_type = match record.stream_view_type.parse() {
+            Ok(DynamoDBStreamViewType::NewImage) => Some(StreamViewType::NewImage),
+            Ok(DynamoDBStreamViewType::OldImage) => Some(StreamViewType::OldImage),
+            Ok(DynamoDBStreamViewType::NewAndOldImage) => Some(StreamViewType::NewAndOldImages),
+            Ok(DynamoDBStreamViewType::KeysOnly) => Some(StreamViewType::KeysOnly),
+            _ => None,
+        };
+        Ok(StreamRecord {
+            approximate_creation_date_time: record
+                .approximate_creation_date_time
+                .and_then(from_float_epoch)
+                .ok_or(InteropError::NoCreationTime)?,
+            keys: to_item(record.keys)?,
+            new_image: to_item(record.new_image)?,
+            old_image: to_item(record.old_image)?,
+            sequence_number: Some(record.sequence_number).filter(|s| !s.is_empty()),
+            size_bytes: record.size_bytes as i64,
+            stream_view_type,
+        })
+    }
+}
+
+impl From<StreamRecord> for DynamoDBStreamRecord {
+    fn from(record: StreamRecord) -> Self {
+        DynamoDBStreamRecord {
+            approximate_creation_date_time: Some(to_float_epoch(
+                &record.approximate_creation_date_time,
+            )),
+            keys: from_item(record.keys),
+            new_image: from_item(record.new_image),
+            old_image: from_item(record.old_image),
+            sequence_number: record.sequence_number.unwrap_or_default(),
+            size_bytes: record.size_bytes.max(0) as u64,
+            stream_view_type: record
+                .stream_view_type
+                .map(|view| view.to_string())
+                .unwrap_or_default(),
+            #[cfg(feature = "extra-fields")]
+            extra: HashMap::new(),
+        }
+    }
+}
+
+impl TryFrom<DynamoDBEventRecord> for EventRecord {
+    type Error = InteropError;
+
+    fn try_from(record: DynamoDBEventRecord) -> Result<Self, Self::Error> {
+        Ok(EventRecord {
+            aws_region: record.aws_region,
+            change: record.dynamodb.try_into()?,
+            event_id: record.event_id,
+            event_name: record.event_name.to_string(),
+            event_source: Some(record.event_source).filter(|s| !s.is_empty()),
+            event_version: Some(record.event_version).filter(|s| !s.is_empty()),
+            event_source_arn: Some(record.event_source_arn).filter(|s| !s.is_empty()),
+            user_identity: record.user_identity.map(|identity| UserIdentity {
+                type_: identity.r#type().to_string(),
+                principal_id: identity.principal_id().to_string(),
+            }),
+            record_format: None,
+            table_name: None,
+        })
+    }
+}
+
+impl From<EventRecord> for DynamoDBEventRecord {
+    fn from(record: EventRecord) -> Self {
+        DynamoDBEventRecord {
+            aws_region: record.aws_region,
+            dynamodb: record.change.into(),
+            event_id: record.event_id,
+            event_name: record
+                .event_name
+                .parse()
+                .unwrap_or(DynamoDBOperationType::Unknown(record.event_name)),
+            event_source: record.event_source.unwrap_or_default(),
+            event_version: record.event_version.unwrap_or_default(),
+            event_source_arn: record.event_source_arn.unwrap_or_default(),
+            user_identity: record.user_identity.map(|identity| {
+                if identity.type_ == "Service" {
+                    DynamoDBUserIdentity::Service {
+                        principal_id: identity.principal_id,
+                    }
+                } else {
+                    DynamoDBUserIdentity::Other {
+                        r#type: identity.type_,
+                        principal_id: identity.principal_id,
+                    }
+                }
+            }),
+            #[cfg(feature = "extra-fields")]
+            extra: HashMap::new(),
+        }
+    }
+}
+
+impl TryFrom<DynamoDBEvent> for Event {
+    type Error = InteropError;
+
+    fn try_from(event: DynamoDBEvent) -> Result<Self, Self::Error> {
+        Ok(Event {
+            records: event
+                .records
+                .into_iter()
+                .map(EventRecord::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<Event> for DynamoDBEvent {
+    fn from(event: Event) -> Self {
+        DynamoDBEvent::new(event.records.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn interop_roundtrip() {
+        for event in &[
+            fixtures::dynamodb::insert_event(),
+            fixtures::dynamodb::modify_event(),
+            fixtures::dynamodb::remove_event(),
+        ] {
+            let theirs = Event::try_from(event.clone()).unwrap();
+            assert_eq!(theirs.records.len(), event.records.len());
+            assert_eq!(DynamoDBEvent::from(theirs), *event);
+        }
+
+        let mut keyless = fixtures::dynamodb::insert_event();
+        keyless.records[0].dynamodb.keys = None;
+        let theirs = Event::try_from(keyless.clone()).unwrap();
+        assert_eq!(DynamoDBEvent::from(theirs), keyless);
+
+        let mut event = fixtures::dynamodb::insert_event();
+        event.records[0].dynamodb.approximate_creation_date_time = None;
+        assert_eq!(
+            Event::try_from(event).unwrap_err(),
+            InteropError::NoCreationTime
+        );
+        let mut event = fixtures::dynamodb::insert_event();
+        let keys = event.records[0].dynamodb.keys.as_mut().unwrap();
+        keys.insert("Empty".to_string(), AttributeValue::default());
+        assert_eq!(
+            Event::try_from(event).unwrap_err(),
+            InteropError::NoAttributeType
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use super::AttributeValue;
+use crate::encoding::base64;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Deserializes a DynamoDB item (or key) into `T`, matching attribute names
+/// against field names with the type descriptors stripped, so
+/// `{"Id": {"N": "1"}, "Name": {"S": "Joe"}}` reads as `{"Id": 1, "Name": "Joe"}`.
+///
+/// Numbers are read as by `item_to_json_lossy`, so `T`'s field types decide
+/// what precision they keep.
+pub fn from_item<T>(item: &HashMap<String, AttributeValue>) -> Result<T, serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_value(item_to_json_lossy(item))
+}
+
+/// Collapses the type descriptors of an item into plain JSON, e.g.
+/// `{"Name": {"S": "Joe"}, "Age": {"N": "35"}}` becomes `{"Name": "Joe", "Age": 35}`.
+///
+/// Sets become arrays, binary values become base64 strings, and numbers that
+/// a JSON number would not hold exactly, such as 38-digit integers or
+/// `1.50`, are kept as strings so no precision is lost.
+pub fn item_to_json(item: &HashMap<String, AttributeValue>) -> Value {
+    Value::Object(item_to_map(item, Numbers::Exact))
+}
+
+/// Like `item_to_json`, but every number within f64's range becomes a JSON
+/// number, rounding those wider than an i64/u64 to the nearest f64. Only a
+/// number out of f64's range is kept as a string.
+pub fn item_to_json_lossy(item: &HashMap<String, AttributeValue>) -> Value {
+    Value::Object(item_to_map(item, Numbers::Rounded))
+}
+
+/// Builds an item from plain JSON: strings become `S`, numbers `N`, arrays
+/// `L` and objects `M`. Returns `None` if `value` is not a JSON object.
+///
+/// This only partly reverses `item_to_json`, as plain JSON loses the type
+/// descriptors: sets come back as `L`, and binary values and the numbers
+/// `item_to_json` kept as strings come back as `S`.
+pub fn json_to_item(value: &Value) -> Option<HashMap<String, AttributeValue>> {
+    match value {
+        Value::Object(map) => Some(map_to_item(map)),
+        _ => None,
+    }
+}
+
+fn map_to_item(map: &Map<String, Value>) -> HashMap<String, AttributeValue> {
+    map.iter()
+        .map(|(name, value)| (name.clone(), json_to_attribute(value)))
+        .collect()
+}
+
+fn json_to_attribute(value: &Value) -> AttributeValue {
+    match value {
+        Value::Null => AttributeValue::null(),
+        Value::Bool(b) => AttributeValue::bool(*b),
+        Value::Number(n) => AttributeValue::n(n),
+        Value::String(s) => AttributeValue::s(s.as_str()),
+        Value::Array(values) => AttributeValue::l(values.iter().map(json_to_attribute).collect()),
+        Value::Object(map) => AttributeValue::m(map_to_item(map)),
+    }
+}
+
+// How `N` values that a JSON number cannot hold exactly are converted.
+#[derive(Clone, Copy)]
+enum Numbers {
+    Exact,
+    Rounded,
+}
+
+fn item_to_map(item: &HashMap<String, AttributeValue>, numbers: Numbers) -> Map<String, Value> {
+    item.iter()
+        .map(|(name, value)| (name.clone(), to_json(value, numbers)))
+        .collect()
+}
+
+// serde_json parses integers wider than an i64/u64, and any fraction or
+// exponent, as the nearest f64, and fails only on numbers out of its range.
+// A lossy parse shows as the number no longer formatting back to its input.
+fn number_to_json(n: &str, numbers: Numbers) -> Value {
+    match (serde_json::from_str::<Number>(n), numbers) {
+        (Ok(number), Numbers::Rounded) => Value::Number(number),
+        (Ok(number), Numbers::Exact) if number.to_string() == n => Value::Number(number),
+        _ => Value::String(n.to_string()),
+    }
+}
+
+pub(crate) fn attribute_to_json(value: &AttributeValue) -> Value {
+    to_json(value, Numbers::Exact)
+}
+
+fn to_json(value: &AttributeValue, numbers: Numbers) -> Value {
+    if let Some(s) = &value.s {
+        Value::String(s.clone())
+    } else if let Some(n) = &value.n {
+        number_to_json(n, numbers)
+    } else if let Some(b) = value.bool {
+        Value::Bool(b)
+    } else if let Some(m) = &value.m {
+        Value::Object(item_to_map(m, numbers))
+    } else if let Some(l) = &value.l {
+        Value::Array(l.iter().map(|value| to_json(value, numbers)).collect())
+    } else if let Some(ss) = &value.ss {
+        Value::Array(ss.iter().cloned().map(Value::String).collect())
+    } else if let Some(ns) = &value.ns {
+        Value::Array(ns.iter().map(|n| number_to_json(n, numbers)).collect())
+    } else if let Some(b) = &value.b {
+        Value::String(base64::encode(b))
+    } else if let Some(bs) = &value.bs {
+        Value::Array(
+            bs.iter()
+                .map(|b| Value::String(base64::encode(b)))
+                .collect(),
+        )
+    } else {
+        Value::Null
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        #[serde(rename = "Id")]
+        id: u64,
+        #[serde(rename = "Name")]
+        name: String,
+        #[serde(rename = "Tags")]
+        tags: Vec<String>,
+        #[serde(rename = "Address")]
+        address: Address,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Address {
+        city: String,
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let item: HashMap<String, AttributeValue> = serde_json::from_value(json!({
+            "Name": {"S": "Joe"},
+            "Age": {"N": "35"},
+            "Active": {"BOOL": true},
+            "Nickname": {"NULL": true},
+            "Pets": {"L": [{"M": {"Name": {"S": "Rex"}}}]}
+        }))
+        .unwrap();
+        let value = json!({
+            "Name": "Joe",
+            "Age": 35,
+            "Active": true,
+            "Nickname": null,
+            "Pets": [{"Name": "Rex"}]
+        });
+        assert_eq!(item_to_json(&item), value);
+        assert_eq!(json_to_item(&value).unwrap(), item);
+        assert_eq!(json_to_item(&json!([1])), None);
+    }
+
+    #[test]
+    fn precise_numbers() {
+        let wide = "12345678901234567890123456789012345678";
+        let item: HashMap<String, AttributeValue> = serde_json::from_value(json!({
+            "Wide": {"N": wide},
+            "Precise": {"N": "0.1000000000000000055511151231257827"},
+            "Scaled": {"N": "1.50"},
+            "Exponent": {"N": "1e3"},
+            "Small": {"N": "-7"},
+            "Huge": {"N": "1e400"},
+            "Ids": {"NS": [wide, "1"]}
+        }))
+        .unwrap();
+        assert_eq!(
+            item_to_json(&item),
+            json!({
+                "Wide": wide,
+                "Precise": "0.1000000000000000055511151231257827",
+                "Scaled": "1.50",
+                "Exponent": "1e3",
+                "Small": -7,
+                "Huge": "1e400",
+                "Ids": [wide, 1]
+            })
+        );
+        assert_eq!(
+            item_to_json_lossy(&item),
+            json!({
+                "Wide": 1.2345678901234568e37,
+                "Precise": 0.1,
+                "Scaled": 1.5,
+                "Exponent": 1000.0,
+                "Small": -7,
+                "Huge": "1e400",
+                "Ids": [1.2345678901234568e37, 1]
+            })
+        );
+        let back = json_to_item(&item_to_json(&item)).unwrap();
+        assert_eq!(back["Scaled"], AttributeValue::s("1.50"));
+        assert_eq!(back["Small"], AttributeValue::n(-7));
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Price {
+            #[serde(rename = "Amount")]
+            amount: f64,
+        }
+        let item: HashMap<String, AttributeValue> =
+            serde_json::from_value(json!({"Amount": {"N": "1.50"}})).unwrap();
+        assert_eq!(from_item::<Price>(&item).unwrap(), Price { amount: 1.5 });
+    }
+
+    #[test]
+    fn typed_item() {
+        let item: HashMap<String, AttributeValue> = serde_json::from_value(json!({
+            "Id": {"N": "42"},
+            "Name": {"S": "Joe"},
+            "Tags": {"SS": ["a", "b"]},
+            "Address": {"M": {"city": {"S": "Leeds"}}}
+        }))
+        .unwrap();
+        let user: User = from_item(&item).unwrap();
+        assert_eq!(
+            user,
+            User {
+                id: 42,
+                name: "Joe".to_string(),
+                tags: vec!["a".to_string(), "b".to_string()],
+                address: Address {
+                    city: "Leeds".to_string()
+                },
+            }
+        );
+    }
+}
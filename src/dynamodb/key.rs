@@ -0,0 +1,126 @@
+use super::{AttributeValue, DynamoDBStreamRecord};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+// Sets are unordered in DynamoDB, so SS, NS and BS compare and hash as sorted
+// copies; lists keep their order. Numbers compare by their string form, so
+// "1" and "1.0" are distinct.
+impl PartialEq for AttributeValue {
+    fn eq(&self, other: &AttributeValue) -> bool {
+        self.b == other.b
+            && self.bool == other.bool
+            && sorted(&self.bs) == sorted(&other.bs)
+            && self.l == other.l
+            && self.m == other.m
+            && self.n == other.n
+            && sorted(&self.ns) == sorted(&other.ns)
+            && self.null == other.null
+            && self.s == other.s
+            && sorted(&self.ss) == sorted(&other.ss)
+    }
+}
+
+impl Hash for AttributeValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.b.hash(state);
+        self.bool.hash(state);
+        sorted(&self.bs).hash(state);
+        self.l.hash(state);
+        // HashMap has no Hash impl, and its iteration order is unspecified.
+        self.m
+            .as_ref()
+            .map(|m| {
+                let mut entries: Vec<(&String, &AttributeValue)> = m.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                entries
+            })
+            .hash(state);
+        self.n.hash(state);
+        sorted(&self.ns).hash(state);
+        self.null.hash(state);
+        self.s.hash(state);
+        sorted(&self.ss).hash(state);
+    }
+}
+
+fn sorted<T: Ord>(set: &Option<Vec<T>>) -> Option<Vec<&T>> {
+    set.as_ref().map(|set| {
+        let mut members: Vec<&T> = set.iter().collect();
+        members.sort();
+        members
+    })
+}
+
+/// A primary key (or any item) that can itself be used in a `HashMap` or
+/// `HashSet`, e.g. to deduplicate records by the item they refer to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ItemKey(pub BTreeMap<String, AttributeValue>);
+
+impl From<HashMap<String, AttributeValue>> for ItemKey {
+    fn from(item: HashMap<String, AttributeValue>) -> ItemKey {
+        ItemKey(item.into_iter().collect())
+    }
+}
+
+impl From<&HashMap<String, AttributeValue>> for ItemKey {
+    fn from(item: &HashMap<String, AttributeValue>) -> ItemKey {
+        ItemKey(
+            item.iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+        )
+    }
+}
+
+impl DynamoDBStreamRecord {
+    /// The record's Keys as a hashable `ItemKey`; empty if Keys is absent.
+    pub fn key(&self) -> ItemKey {
+        self.keys.as_ref().map(ItemKey::from).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn hashable_keys() {
+        let mut a = HashMap::new();
+        a.insert("Id".to_string(), AttributeValue::s("1"));
+        a.insert("Sort".to_string(), AttributeValue::n(2));
+        let mut nested = HashMap::new();
+        nested.insert("Inner".to_string(), AttributeValue::m(a.clone()));
+
+        let mut keys = HashSet::new();
+        assert!(keys.insert(ItemKey::from(&a)));
+        assert!(!keys.insert(ItemKey::from(a.clone())));
+        assert!(keys.insert(ItemKey::from(&nested)));
+        assert!(!keys.insert(ItemKey::from(nested)));
+
+        let mut values = HashSet::new();
+        values.insert(AttributeValue::m(a.clone()));
+        assert!(values.contains(&AttributeValue::m(a)));
+    }
+
+    #[test]
+    fn sets_are_unordered() {
+        let ab = AttributeValue::ss(vec!["a", "b"]);
+        let ba = AttributeValue::ss(vec!["b", "a"]);
+        assert_eq!(ab, ba);
+        let mut values = HashSet::new();
+        values.insert(ab);
+        assert!(values.contains(&ba));
+
+        assert_eq!(
+            AttributeValue::ns(vec![1, 2]),
+            AttributeValue::ns(vec![2, 1])
+        );
+        assert_ne!(
+            AttributeValue::l(vec![AttributeValue::s("a"), AttributeValue::s("b")]),
+            AttributeValue::l(vec![AttributeValue::s("b"), AttributeValue::s("a")])
+        );
+    }
+}
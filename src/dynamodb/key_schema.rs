@@ -0,0 +1,107 @@
+use super::{AttributeValue, DynamoDBEventRecord, DynamoDBKeyType};
+use serde::{Deserialize, Serialize};
+
+/// One attribute of a table's primary key, in the shape DescribeTable returns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeySchemaElement {
+    #[serde(rename = "AttributeName")]
+    pub attribute_name: String,
+    #[serde(rename = "KeyType")]
+    pub key_type: DynamoDBKeyType,
+}
+
+/// A table's primary key: a HASH (partition) attribute and optionally a
+/// RANGE (sort) attribute. Stream records don't say which of their Keys is
+/// which, so this is needed to tell them apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeySchema(pub Vec<KeySchemaElement>);
+
+impl KeySchema {
+    /// A schema with only a partition key.
+    pub fn hash<S: Into<String>>(partition_key: S) -> KeySchema {
+        KeySchema(vec![KeySchemaElement {
+            attribute_name: partition_key.into(),
+            key_type: DynamoDBKeyType::Hash,
+        }])
+    }
+
+    /// A schema with a partition key and a sort key.
+    pub fn hash_range<P: Into<String>, S: Into<String>>(
+        partition_key: P,
+        sort_key: S,
+    ) -> KeySchema {
+        let mut schema = KeySchema::hash(partition_key);
+        schema.0.push(KeySchemaElement {
+            attribute_name: sort_key.into(),
+            key_type: DynamoDBKeyType::Range,
+        });
+        schema
+    }
+
+    /// The name of the attribute with the given key type.
+    pub fn attribute_name(&self, key_type: &DynamoDBKeyType) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|element| element.key_type == *key_type)
+            .map(|element| element.attribute_name.as_str())
+    }
+
+    pub fn partition_key_name(&self) -> Option<&str> {
+        self.attribute_name(&DynamoDBKeyType::Hash)
+    }
+
+    pub fn sort_key_name(&self) -> Option<&str> {
+        self.attribute_name(&DynamoDBKeyType::Range)
+    }
+}
+
+impl DynamoDBEventRecord {
+    /// The partition key value from the record's Keys.
+    pub fn partition_key(&self, schema: &KeySchema) -> Option<&AttributeValue> {
+        self.key_attribute(schema, &DynamoDBKeyType::Hash)
+    }
+
+    /// The sort key value from the record's Keys, if the table has one.
+    pub fn sort_key(&self, schema: &KeySchema) -> Option<&AttributeValue> {
+        self.key_attribute(schema, &DynamoDBKeyType::Range)
+    }
+
+    fn key_attribute(
+        &self,
+        schema: &KeySchema,
+        key_type: &DynamoDBKeyType,
+    ) -> Option<&AttributeValue> {
+        let name = schema.attribute_name(key_type)?;
+        self.dynamodb.keys.as_ref()?.get(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn schema_keys() {
+        let record = &fixtures::dynamodb::insert_event().records[0];
+        let keys = record.dynamodb.keys.as_ref().unwrap();
+        let (name, value) = keys.iter().next().unwrap();
+
+        let schema = KeySchema::hash(name.as_str());
+        assert_eq!(record.partition_key(&schema), Some(value));
+        assert_eq!(record.sort_key(&schema), None);
+        assert_eq!(
+            record.sort_key(&KeySchema::hash_range("Missing", name.as_str())),
+            Some(value)
+        );
+
+        let parsed: KeySchema = serde_json::from_str(
+            r#"[{"AttributeName": "Id", "KeyType": "HASH"},
+                {"AttributeName": "CreatedAt", "KeyType": "RANGE"}]"#,
+        )
+        .unwrap();
+        assert_eq!(parsed, KeySchema::hash_range("Id", "CreatedAt"));
+        assert_eq!(parsed.sort_key_name(), Some("CreatedAt"));
+    }
+}
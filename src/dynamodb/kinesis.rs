@@ -0,0 +1,96 @@
+use super::{AttributeValue, DynamoDBEventRecord, DynamoDBOperationType, DynamoDBUserIdentity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A change record in the shape Kinesis Data Streams for DynamoDB writes to
+/// the stream: the record's `data`, once base64-decoded and parsed.
+///
+/// Unlike a DynamoDB Streams record it names the table directly, carries no
+/// SequenceNumber or StreamViewType, and gives ApproximateCreationDateTime in
+/// milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KinesisPayload {
+    #[serde(rename = "awsRegion")]
+    pub aws_region: String,
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    #[serde(rename = "eventName")]
+    pub event_name: DynamoDBOperationType,
+    #[serde(rename = "userIdentity")]
+    pub user_identity: Option<DynamoDBUserIdentity>,
+    #[serde(rename = "recordFormat")]
+    pub record_format: String,
+    #[serde(rename = "tableName")]
+    pub table_name: String,
+    #[serde(rename = "dynamodb")]
+    pub dynamodb: KinesisStreamRecord,
+    #[serde(rename = "eventSource")]
+    pub event_source: String,
+}
+
+/// The `dynamodb` member of a `KinesisPayload`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KinesisStreamRecord {
+    // Epoch milliseconds.
+    #[serde(rename = "ApproximateCreationDateTime")]
+    pub approximate_creation_date_time: Option<u64>,
+    #[serde(rename = "Keys")]
+    pub keys: Option<HashMap<String, AttributeValue>>,
+    #[serde(rename = "NewImage", skip_serializing_if = "Option::is_none")]
+    pub new_image: Option<HashMap<String, AttributeValue>>,
+    #[serde(rename = "OldImage", skip_serializing_if = "Option::is_none")]
+    pub old_image: Option<HashMap<String, AttributeValue>>,
+    #[serde(rename = "SizeBytes")]
+    pub size_bytes: u64,
+}
+
+impl DynamoDBEventRecord {
+    /// The record as Kinesis Data Streams for DynamoDB would have delivered
+    /// it, so consumers migrating between the two can share one code path.
+    pub fn to_kinesis_payload(&self) -> KinesisPayload {
+        let stream = &self.dynamodb;
+        KinesisPayload {
+            aws_region: self.aws_region.clone(),
+            event_id: self.event_id.clone(),
+            event_name: self.event_name.clone(),
+            user_identity: self.user_identity.clone(),
+            record_format: "application/json".to_string(),
+            table_name: self.table_name().unwrap_or_default().to_string(),
+            dynamodb: KinesisStreamRecord {
+                approximate_creation_date_time: stream
+                    .approximate_creation_date_time
+                    .map(|secs| (secs * 1000.0).round() as u64),
+                keys: stream.keys.clone(),
+                new_image: stream.new_image.clone(),
+                old_image: stream.old_image.clone(),
+                size_bytes: stream.size_bytes,
+            },
+            event_source: self.event_source.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::fixtures;
+    use serde_json::json;
+
+    #[test]
+    fn kinesis_shape() {
+        let record = &fixtures::dynamodb::insert_event().records[0];
+        let payload = serde_json::to_value(record.to_kinesis_payload()).unwrap();
+
+        assert_eq!(payload["tableName"], json!("BarkTable"));
+        assert_eq!(payload["recordFormat"], json!("application/json"));
+        assert_eq!(
+            payload["dynamodb"]["ApproximateCreationDateTime"],
+            json!(1479499740000u64)
+        );
+        assert!(payload["dynamodb"].get("OldImage").is_none());
+        assert!(payload["dynamodb"].get("SequenceNumber").is_none());
+        assert_eq!(
+            payload["dynamodb"]["NewImage"]["Username"],
+            json!({"S": "John Doe"})
+        );
+    }
+}
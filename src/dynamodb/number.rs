@@ -0,0 +1,125 @@
+use super::AttributeValue;
+use std::fmt;
+
+/// The value of an `N` attribute.
+///
+/// DynamoDB numbers carry up to 38 significant digits, more than any primitive
+/// type can hold, so the original string is kept and each conversion reports
+/// whether it fits rather than silently rounding through `f64`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynamoDBNumber(String);
+
+impl DynamoDBNumber {
+    pub fn new<S: Into<String>>(value: S) -> DynamoDBNumber {
+        DynamoDBNumber(value.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// `None` unless the number is an integer within range.
+    pub fn to_i64(&self) -> Option<i64> {
+        self.0.parse().ok()
+    }
+
+    /// `None` unless the number is a non-negative integer within range.
+    pub fn to_u64(&self) -> Option<u64> {
+        self.0.parse().ok()
+    }
+
+    /// The nearest `f64`, which may lose precision.
+    pub fn to_f64(&self) -> Option<f64> {
+        self.0.parse().ok()
+    }
+
+    /// The number as a `Decimal`. `rust_decimal` holds 28 significant digits,
+    /// so `None` is returned for the rare values beyond that.
+    #[cfg(feature = "rust_decimal")]
+    pub fn to_decimal(&self) -> Option<rust_decimal::Decimal> {
+        let decimal: rust_decimal::Decimal = if self.0.contains(['e', 'E']) {
+            rust_decimal::Decimal::from_scientific(&self.0).ok()?
+        } else {
+            self.0.parse().ok()?
+        };
+        // rust_decimal rounds what it cannot hold instead of failing, so the
+        // parse only counts if every significant digit survived it.
+        let exact = significant_digits(&self.0) == significant_digits(&decimal.to_string());
+        exact.then_some(decimal)
+    }
+}
+
+// The digits of a number without its sign, point, exponent, or leading and
+// trailing zeros.
+#[cfg(feature = "rust_decimal")]
+fn significant_digits(number: &str) -> String {
+    let mantissa = number.split(['e', 'E']).next().unwrap_or_default();
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    digits.trim_matches('0').to_owned()
+}
+
+impl fmt::Display for DynamoDBNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl From<rust_decimal::Decimal> for DynamoDBNumber {
+    fn from(value: rust_decimal::Decimal) -> DynamoDBNumber {
+        DynamoDBNumber(value.to_string())
+    }
+}
+
+impl From<DynamoDBNumber> for AttributeValue {
+    fn from(value: DynamoDBNumber) -> AttributeValue {
+        AttributeValue::n(value)
+    }
+}
+
+impl AttributeValue {
+    pub fn as_number(&self) -> Option<DynamoDBNumber> {
+        self.as_n().map(DynamoDBNumber::new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn number_conversions() {
+        let number = AttributeValue::n("-19").as_number().unwrap();
+        assert_eq!(number.to_i64(), Some(-19));
+        assert_eq!(number.to_u64(), None);
+        assert_eq!(number.to_f64(), Some(-19.0));
+
+        let big = DynamoDBNumber::new("12345678901234567890123456789012345678");
+        assert_eq!(big.to_i64(), None);
+        assert_eq!(big.as_str(), "12345678901234567890123456789012345678");
+        assert!(AttributeValue::s("1").as_number().is_none());
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn decimal_conversion() {
+        let number = DynamoDBNumber::new("3.14159");
+        assert_eq!(number.to_decimal().unwrap().to_string(), "3.14159");
+        assert_eq!(
+            DynamoDBNumber::new("1.5e2").to_decimal().unwrap(),
+            rust_decimal::Decimal::new(150, 0)
+        );
+        assert_eq!(
+            DynamoDBNumber::new("-0.00100").to_decimal().unwrap(),
+            rust_decimal::Decimal::new(-1, 3)
+        );
+        assert_eq!(
+            DynamoDBNumber::new("0.12345678901234567890123456789012345678").to_decimal(),
+            None
+        );
+        assert_eq!(
+            DynamoDBNumber::new("12345678901234567890123456789012345678").to_decimal(),
+            None
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Normalizes stream records into change events, the before/after shape most
+//! message buses and outbox consumers expect.
+
+use super::{from_item, item_to_json, DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single change to an item, with the images deserialized into `T`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent<T> {
+    pub operation: DynamoDBOperationType,
+    /// The item's primary key as plain JSON, e.g. `{"Id": 1}`.
+    pub key: serde_json::Value,
+    /// The item before the change; absent for inserts and for streams that
+    /// don't capture old images.
+    pub before: Option<T>,
+    /// The item after the change; absent for removes and for streams that
+    /// don't capture new images.
+    pub after: Option<T>,
+    /// ApproximateCreationDateTime, in fractional epoch seconds.
+    pub timestamp: Option<f64>,
+    pub source_table: Option<String>,
+    pub sequence_number: String,
+}
+
+impl<T: DeserializeOwned> ChangeEvent<T> {
+    pub fn from_record(record: &DynamoDBEventRecord) -> Result<ChangeEvent<T>, serde_json::Error> {
+        let stream = &record.dynamodb;
+        Ok(ChangeEvent {
+            operation: record.event_name.clone(),
+            key: item_to_json(stream.keys.as_ref().unwrap_or(&HashMap::new())),
+            before: stream.old_image.as_ref().map(from_item).transpose()?,
+            after: stream.new_image.as_ref().map(from_item).transpose()?,
+            timestamp: stream.approximate_creation_date_time,
+            source_table: record.table_name().map(str::to_string),
+            sequence_number: stream.sequence_number.clone(),
+        })
+    }
+}
+
+impl DynamoDBEvent {
+    /// Every record as a `ChangeEvent<T>`, in order.
+    pub fn change_events<'a, T>(
+        &'a self,
+    ) -> impl Iterator<Item = Result<ChangeEvent<T>, serde_json::Error>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        self.records.iter().map(ChangeEvent::from_record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        #[serde(rename = "Username")]
+        username: String,
+    }
+
+    #[test]
+    fn change_events() {
+        let mut event = fixtures::dynamodb::insert_event();
+        event
+            .records
+            .extend(fixtures::dynamodb::remove_event().records);
+        let changes: Vec<ChangeEvent<Item>> =
+            event.change_events().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(changes[0].operation, DynamoDBOperationType::Insert);
+        assert!(changes[0].before.is_none());
+        assert_eq!(changes[0].after.as_ref().unwrap().username, "John Doe");
+        assert_eq!(changes[0].source_table.as_deref(), Some("BarkTable"));
+        assert_eq!(changes[0].key["Username"], json!("John Doe"));
+
+        assert_eq!(changes[1].operation, DynamoDBOperationType::Remove);
+        assert!(changes[1].after.is_none());
+        assert!(changes[1].before.is_some());
+    }
+}
@@ -0,0 +1,103 @@
+use super::diff::{AttributePath, ImageDiff, PathSegment};
+use super::item::attribute_to_json;
+use super::DynamoDBStreamRecord;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A single RFC 6902 JSON Patch operation. Values are plain JSON, as
+/// produced by `item_to_json`, so a patch applies to the item's JSON form.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+impl AttributePath {
+    /// The path as an RFC 6901 JSON Pointer, e.g. `/user/address/0/city`.
+    pub fn to_json_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for segment in &self.0 {
+            pointer.push('/');
+            match segment {
+                PathSegment::Attribute(name) => {
+                    pointer.push_str(&name.replace('~', "~0").replace('/', "~1"))
+                }
+                PathSegment::Index(index) => pointer.push_str(&index.to_string()),
+            }
+        }
+        pointer
+    }
+}
+
+impl ImageDiff {
+    /// The diff as a JSON Patch that turns the old image into the new one.
+    ///
+    /// Operations are ordered so the patch applies sequentially: replacements
+    /// first, then removals from the highest list index down, then additions.
+    pub fn to_json_patch(&self) -> Vec<PatchOperation> {
+        let replaced = self.changed.iter().map(|change| PatchOperation::Replace {
+            path: change.path.to_json_pointer(),
+            value: attribute_to_json(&change.new),
+        });
+        let removed = self
+            .removed
+            .iter()
+            .rev()
+            .map(|(path, _)| PatchOperation::Remove {
+                path: path.to_json_pointer(),
+            });
+        let added = self.added.iter().map(|(path, value)| PatchOperation::Add {
+            path: path.to_json_pointer(),
+            value: attribute_to_json(value),
+        });
+        replaced.chain(removed).chain(added).collect()
+    }
+}
+
+impl DynamoDBStreamRecord {
+    /// The change this record describes as a JSON Patch against the old
+    /// image's JSON form; see `ImageDiff::to_json_patch`.
+    pub fn to_json_patch(&self) -> Vec<PatchOperation> {
+        self.diff().to_json_patch()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dynamodb::AttributeValue;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn item(value: Value) -> HashMap<String, AttributeValue> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn patch_operations() {
+        let old = item(json!({
+            "Gone": {"BOOL": true},
+            "a/b": {"S": "x"},
+            "Tags": {"L": [{"S": "a"}, {"S": "b"}, {"S": "c"}]}
+        }));
+        let new = item(json!({
+            "a/b": {"S": "y"},
+            "Count": {"N": "3"},
+            "Tags": {"L": [{"S": "a"}]}
+        }));
+        let patch = ImageDiff::between(&old, &new).to_json_patch();
+
+        assert_eq!(
+            serde_json::to_value(&patch).unwrap(),
+            json!([
+                {"op": "replace", "path": "/a~1b", "value": "y"},
+                {"op": "remove", "path": "/Tags/2"},
+                {"op": "remove", "path": "/Tags/1"},
+                {"op": "remove", "path": "/Gone"},
+                {"op": "add", "path": "/Count", "value": 3}
+            ])
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use super::diff::{AttributePath, PathSegment};
+use super::AttributeValue;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Returned when a document path such as `user.address[0].city` is malformed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsePathError {
+    path: String,
+    position: usize,
+}
+
+impl fmt::Display for ParsePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid attribute path {:?} at byte {}",
+            self.path, self.position
+        )
+    }
+}
+
+impl Error for ParsePathError {}
+
+/// Parses a document path: attribute names separated by `.`, list indexes in
+/// brackets. A name containing `.`, `[`, `]` or `\` escapes it with `\`,
+/// e.g. `tags.a\.b`.
+impl FromStr for AttributePath {
+    type Err = ParsePathError;
+
+    fn from_str(s: &str) -> Result<AttributePath, ParsePathError> {
+        let error = |position| ParsePathError {
+            path: s.to_string(),
+            position,
+        };
+        let mut segments = Vec::new();
+        let mut chars = s.char_indices().peekable();
+        // A name is expected at the start and after every `.`.
+        let mut expect_name = true;
+        while let Some(&(start, c)) = chars.peek() {
+            if c == '[' {
+                chars.next();
+                let mut digits = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if !c.is_ascii_digit() {
+                        break;
+                    }
+                    digits.push(c);
+                    chars.next();
+                }
+                match (chars.next(), digits.parse()) {
+                    (Some((_, ']')), Ok(index)) if !expect_name => {
+                        segments.push(PathSegment::Index(index))
+                    }
+                    _ => return Err(error(start)),
+                }
+            } else if c == '.' {
+                if expect_name {
+                    return Err(error(start));
+                }
+                chars.next();
+                expect_name = true;
+            } else {
+                if !expect_name {
+                    return Err(error(start));
+                }
+                let mut name = String::new();
+                while let Some(&(position, c)) = chars.peek() {
+                    match c {
+                        '.' | '[' => break,
+                        ']' => return Err(error(position)),
+                        '\\' => {
+                            chars.next();
+                            match chars.next() {
+                                Some((_, c)) => name.push(c),
+                                None => return Err(error(position)),
+                            }
+                            continue;
+                        }
+                        c => name.push(c),
+                    }
+                    chars.next();
+                }
+                segments.push(PathSegment::Attribute(name));
+                expect_name = false;
+            }
+        }
+        if expect_name {
+            return Err(error(s.len()));
+        }
+        Ok(AttributePath(segments))
+    }
+}
+
+impl AttributeValue {
+    /// The value at `path` below this one, descending through M and L values.
+    pub fn get_at(&self, path: &AttributePath) -> Option<&AttributeValue> {
+        descend(self, &path.0)
+    }
+
+    /// Like `get_at`, parsing `path` first; `None` if it is malformed.
+    pub fn get_path(&self, path: &str) -> Option<&AttributeValue> {
+        self.get_at(&path.parse().ok()?)
+    }
+}
+
+fn descend<'a>(value: &'a AttributeValue, segments: &[PathSegment]) -> Option<&'a AttributeValue> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match segment {
+            PathSegment::Attribute(name) => value.m.as_ref()?.get(name),
+            PathSegment::Index(index) => value.l.as_ref()?.get(*index),
+        })
+}
+
+/// Document-path lookups on an item, e.g. a stream record's NewImage:
+/// `image.get_path("user.address[0].city")`.
+pub trait ItemPath {
+    fn get_at(&self, path: &AttributePath) -> Option<&AttributeValue>;
+
+    /// Like `get_at`, parsing `path` first; `None` if it is malformed.
+    fn get_path(&self, path: &str) -> Option<&AttributeValue> {
+        self.get_at(&path.parse().ok()?)
+    }
+}
+
+impl ItemPath for HashMap<String, AttributeValue> {
+    fn get_at(&self, path: &AttributePath) -> Option<&AttributeValue> {
+        match path.0.split_first()? {
+            (PathSegment::Attribute(name), rest) => descend(self.get(name)?, rest),
+            (PathSegment::Index(_), _) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_paths() {
+        let path: AttributePath = r"user.address[0][12].a\.b\\".parse().unwrap();
+        assert_eq!(
+            path.0,
+            vec![
+                PathSegment::Attribute("user".to_string()),
+                PathSegment::Attribute("address".to_string()),
+                PathSegment::Index(0),
+                PathSegment::Index(12),
+                PathSegment::Attribute(r"a.b\".to_string()),
+            ]
+        );
+        for bad in &[
+            "", "a.", ".a", "a..b", "[0]", "a[x]", "a[0", "a]b", "a[0]b", r"a\",
+        ] {
+            assert!(bad.parse::<AttributePath>().is_err(), "{:?}", bad);
+        }
+        assert_eq!(
+            "a[x]".parse::<AttributePath>().unwrap_err().to_string(),
+            r#"invalid attribute path "a[x]" at byte 1"#
+        );
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        let path = AttributePath(vec![
+            PathSegment::Attribute("a.b".to_string()),
+            PathSegment::Index(3),
+            PathSegment::Attribute(r"x[1]\y".to_string()),
+            PathSegment::Attribute("plain".to_string()),
+        ]);
+        let printed = path.to_string();
+        assert_eq!(printed, r"a\.b[3].x\[1\]\\y.plain");
+        assert_eq!(printed.parse::<AttributePath>().unwrap(), path);
+    }
+
+    #[test]
+    fn get_paths() {
+        let item: HashMap<String, AttributeValue> = serde_json::from_value(json!({
+            "user": {"M": {"address": {"L": [{"M": {"city": {"S": "Leeds"}}}]}}},
+            "a.b": {"N": "1"}
+        }))
+        .unwrap();
+        assert_eq!(
+            item.get_path("user.address[0].city"),
+            Some(&AttributeValue::s("Leeds"))
+        );
+        assert_eq!(item.get_path(r"a\.b"), Some(&AttributeValue::n(1)));
+        assert_eq!(item.get_path("user.address[1].city"), None);
+        assert_eq!(item.get_path("user[0]"), None);
+        assert_eq!(item.get_path("user..address"), None);
+    }
+}
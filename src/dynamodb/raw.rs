@@ -0,0 +1,128 @@
+use super::{
+    DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType, DynamoDBStreamRecord,
+    DynamoDBUserIdentity,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+#[cfg(feature = "extra-fields")]
+use std::collections::HashMap;
+
+/// A `DynamoDBEvent` whose records leave their `dynamodb` body (keys and
+/// images) unparsed, so handlers that filter on eventName or the source ARN
+/// only pay for deserializing the records they keep.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct RawDynamoDBEvent {
+    #[serde(rename = "Records")]
+    pub records: Vec<RawDynamoDBEventRecord>,
+}
+
+impl RawDynamoDBEvent {
+    /// Parses every record; equivalent to parsing the event as `DynamoDBEvent`.
+    pub fn parse(&self) -> Result<DynamoDBEvent, serde_json::Error> {
+        let records = self
+            .records
+            .iter()
+            .map(RawDynamoDBEventRecord::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(DynamoDBEvent { records })
+    }
+}
+
+/// A `DynamoDBEventRecord` with its `dynamodb` body kept as raw JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    all(feature = "strict", not(feature = "extra-fields")),
+    serde(deny_unknown_fields)
+)]
+pub struct RawDynamoDBEventRecord {
+    #[serde(default, rename = "awsRegion")]
+    pub aws_region: String,
+    #[serde(rename = "dynamodb")]
+    pub dynamodb: Box<RawValue>,
+    #[serde(rename = "eventID")]
+    pub event_id: String,
+    #[serde(rename = "eventName")]
+    pub event_name: DynamoDBOperationType,
+    #[serde(default, rename = "eventSource")]
+    pub event_source: String,
+    #[serde(default, rename = "eventVersion")]
+    pub event_version: String,
+    #[serde(default, rename = "eventSourceARN")]
+    pub event_source_arn: String,
+    #[serde(rename = "userIdentity")]
+    pub user_identity: Option<DynamoDBUserIdentity>,
+    #[cfg(feature = "extra-fields")]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl RawDynamoDBEventRecord {
+    /// Parses just the `dynamodb` body.
+    pub fn parse_stream_record(&self) -> Result<DynamoDBStreamRecord, serde_json::Error> {
+        serde_json::from_str(self.dynamodb.get())
+    }
+
+    /// Parses the `dynamodb` body and returns the complete record.
+    pub fn parse(&self) -> Result<DynamoDBEventRecord, serde_json::Error> {
+        Ok(DynamoDBEventRecord {
+            aws_region: self.aws_region.clone(),
+            dynamodb: self.parse_stream_record()?,
+            event_id: self.event_id.clone(),
+            event_name: self.event_name.clone(),
+            event_source: self.event_source.clone(),
+            event_version: self.event_version.clone(),
+            event_source_arn: self.event_source_arn.clone(),
+            user_identity: self.user_identity.clone(),
+            #[cfg(feature = "extra-fields")]
+            extra: self.extra.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn lazy_records() {
+        let data = fixtures::dynamodb::MODIFY_EVENT_JSON;
+        let raw: RawDynamoDBEvent = serde_json::from_slice(data).unwrap();
+        let record = &raw.records[0];
+        assert_eq!(record.event_name, DynamoDBOperationType::Modify);
+        assert!(record.dynamodb.get().contains("NewImage"));
+
+        let event: DynamoDBEvent = serde_json::from_slice(data).unwrap();
+        assert_eq!(raw.parse().unwrap(), event);
+        assert_eq!(
+            record.parse_stream_record().unwrap(),
+            event.records[0].dynamodb
+        );
+    }
+
+    #[cfg(feature = "extra-fields")]
+    #[test]
+    fn extra_fields_match_owned() {
+        let mut value: serde_json::Value =
+            serde_json::from_slice(fixtures::dynamodb::INSERT_EVENT_JSON).unwrap();
+        value["Records"][0]["recordFormat"] = "application/json".into();
+        value["Records"][0]["dynamodb"]["ApproximateCreationDateTimePrecision"] =
+            "MICROSECOND".into();
+        let data = serde_json::to_vec(&value).unwrap();
+
+        let owned: DynamoDBEvent = serde_json::from_slice(&data).unwrap();
+        let raw: RawDynamoDBEvent = serde_json::from_slice(&data).unwrap();
+        assert_eq!(raw.records[0].extra["recordFormat"], "application/json");
+        assert_eq!(raw.parse().unwrap(), owned);
+    }
+
+    #[cfg(all(feature = "strict", not(feature = "extra-fields")))]
+    #[test]
+    fn strict_rejects_unknown_fields() {
+        let mut value: serde_json::Value =
+            serde_json::from_slice(fixtures::dynamodb::INSERT_EVENT_JSON).unwrap();
+        value["Records"][0]["recordFormat"] = "application/json".into();
+        assert!(serde_json::from_value::<RawDynamoDBEvent>(value).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use super::{DynamoDBEvent, DynamoDBEventRecord};
+use std::error::Error;
+use std::fmt;
+
+/// An error raised while handling one record, tagged with enough context to
+/// find the record again: its eventID, SequenceNumber and table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordError<E> {
+    pub event_id: String,
+    pub sequence_number: String,
+    pub table_name: Option<String>,
+    pub error: E,
+}
+
+impl<E> RecordError<E> {
+    pub fn new(record: &DynamoDBEventRecord, error: E) -> RecordError<E> {
+        RecordError {
+            event_id: record.event_id.clone(),
+            sequence_number: record.dynamodb.sequence_number.clone(),
+            table_name: record.table_name().map(str::to_string),
+            error,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RecordError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "record {} (sequence number {}, table {}): {}",
+            self.event_id,
+            self.sequence_number,
+            self.table_name.as_deref().unwrap_or("unknown"),
+            self.error
+        )
+    }
+}
+
+impl<E: Error + 'static> Error for RecordError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl DynamoDBEvent {
+    /// Applies `f` to every record, in order, wrapping each failure in a
+    /// `RecordError`. Every record is attempted, so the result lines up with
+    /// `records` and can drive partial-batch failure reporting.
+    pub fn try_map_records<F, T, E>(&self, mut f: F) -> Vec<Result<T, RecordError<E>>>
+    where
+        F: FnMut(&DynamoDBEventRecord) -> Result<T, E>,
+    {
+        self.records
+            .iter()
+            .map(|record| f(record).map_err(|error| RecordError::new(record, error)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamodb::DynamoDBOperationType;
+    use crate::fixtures;
+
+    #[test]
+    fn tagged_errors() {
+        let mut event = fixtures::dynamodb::insert_event();
+        event
+            .records
+            .extend(fixtures::dynamodb::remove_event().records);
+        let results = event.try_map_records(|record| match record.event_name {
+            DynamoDBOperationType::Remove => Err("deletes are not supported"),
+            _ => Ok(record.event_id.len()),
+        });
+
+        assert_eq!(results[0], Ok(32));
+        let err = results[1].as_ref().unwrap_err();
+        assert_eq!(err.sequence_number, "13021600000000001596893681");
+        assert_eq!(
+            err.to_string(),
+            "record 4b25bd0da9a181a155114127e4837252 (sequence number 13021600000000001596893681, \
+             table BarkTable): deletes are not supported"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Conversions to and from `rusoto_dynamodb::AttributeValue`, which has the
+//! same shape as this crate's `AttributeValue` but uses `bytes` 1.x.
+
+use super::AttributeValue;
+use rusoto_dynamodb::AttributeValue as RusotoAttributeValue;
+use std::collections::HashMap;
+
+impl From<AttributeValue> for RusotoAttributeValue {
+    fn from(value: AttributeValue) -> Self {
+        RusotoAttributeValue {
+            b: value.b.map(|b| b.to_vec().into()),
+            bool: value.bool,
+            bs: value
+                .bs
+                .map(|bs| bs.iter().map(|b| b.to_vec().into()).collect()),
+            l: value.l.map(|l| l.into_iter().map(Into::into).collect()),
+            m: value.m.map(to_rusoto_item),
+            n: value.n,
+            ns: value.ns,
+            null: value.null,
+            s: value.s,
+            ss: value.ss,
+        }
+    }
+}
+
+impl From<RusotoAttributeValue> for AttributeValue {
+    fn from(value: RusotoAttributeValue) -> Self {
+        AttributeValue {
+            b: value.b.map(|b| b.to_vec().into()),
+            bool: value.bool,
+            bs: value
+                .bs
+                .map(|bs| bs.iter().map(|b| b.to_vec().into()).collect()),
+            l: value.l.map(|l| l.into_iter().map(Into::into).collect()),
+            m: value.m.map(from_rusoto_item),
+            n: value.n,
+            ns: value.ns,
+            null: value.null,
+            s: value.s,
+            ss: value.ss,
+        }
+    }
+}
+
+/// Converts an item (e.g. a stream record's `NewImage`) for use with rusoto.
+pub fn to_rusoto_item(
+    item: HashMap<String, AttributeValue>,
+) -> HashMap<String, RusotoAttributeValue> {
+    item.into_iter()
+        .map(|(name, value)| (name, value.into()))
+        .collect()
+}
+
+/// Converts an item returned by rusoto into this crate's representation.
+pub fn from_rusoto_item(
+    item: HashMap<String, RusotoAttributeValue>,
+) -> HashMap<String, AttributeValue> {
+    item.into_iter()
+        .map(|(name, value)| (name, value.into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rusoto_roundtrip() {
+        let mut item = HashMap::new();
+        item.insert("Name".to_string(), AttributeValue::s("Joe"));
+        item.insert("Avatar".to_string(), AttributeValue::b(&b"png"[..]));
+        item.insert(
+            "Pets".to_string(),
+            AttributeValue::l(vec![AttributeValue::m(item.clone())]),
+        );
+
+        let rusoto = to_rusoto_item(item.clone());
+        assert_eq!(rusoto["Name"].s.as_deref(), Some("Joe"));
+        assert_eq!(from_rusoto_item(rusoto), item);
+    }
+}
@@ -0,0 +1,220 @@
+use super::builder::DynamoDBEventBuilder;
+use super::{AttributeValue, DynamoDBEventRecord};
+use crate::canonical::sorted;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+// The layout of `sam local generate-event dynamodb update`: fields in the
+// order SAM writes them, no ApproximateCreationDateTime or userIdentity, and
+// two-space indentation. It is not byte-for-byte SAM's output: items are
+// HashMaps, so attributes are sorted by name where SAM keeps its template's
+// order, and eventVersion is the record's (1.1 from the builder, where SAM's
+// template has 1.0).
+#[derive(Serialize)]
+struct SampleEvent<'a> {
+    #[serde(rename = "Records")]
+    records: Vec<SampleRecord<'a>>,
+}
+
+#[derive(Serialize)]
+struct SampleRecord<'a> {
+    #[serde(rename = "eventID")]
+    event_id: &'a str,
+    #[serde(rename = "eventVersion")]
+    event_version: &'a str,
+    #[serde(rename = "dynamodb")]
+    dynamodb: SampleStreamRecord<'a>,
+    #[serde(rename = "awsRegion")]
+    aws_region: &'a str,
+    #[serde(rename = "eventName")]
+    event_name: &'a str,
+    #[serde(rename = "eventSourceARN")]
+    event_source_arn: &'a str,
+    #[serde(rename = "eventSource")]
+    event_source: &'a str,
+}
+
+#[derive(Serialize)]
+struct SampleStreamRecord<'a> {
+    #[serde(rename = "Keys", skip_serializing_if = "Option::is_none")]
+    keys: Option<BTreeMap<&'a str, Value>>,
+    #[serde(rename = "NewImage", skip_serializing_if = "Option::is_none")]
+    new_image: Option<BTreeMap<&'a str, Value>>,
+    #[serde(rename = "OldImage", skip_serializing_if = "Option::is_none")]
+    old_image: Option<BTreeMap<&'a str, Value>>,
+    #[serde(rename = "StreamViewType")]
+    stream_view_type: &'a str,
+    #[serde(rename = "SequenceNumber")]
+    sequence_number: &'a str,
+    #[serde(rename = "SizeBytes")]
+    size_bytes: u64,
+}
+
+// Nested map attributes are sorted explicitly, as serde_json's Map keeps
+// insertion order when its `preserve_order` feature is enabled.
+fn sample_item(item: &Option<HashMap<String, AttributeValue>>) -> Option<BTreeMap<&str, Value>> {
+    item.as_ref().map(|item| {
+        item.iter()
+            .map(|(name, value)| {
+                let value = serde_json::to_value(value).expect("attribute values serialize");
+                (name.as_str(), sorted(value))
+            })
+            .collect()
+    })
+}
+
+impl<'a> From<&'a DynamoDBEventRecord> for SampleRecord<'a> {
+    fn from(record: &'a DynamoDBEventRecord) -> SampleRecord<'a> {
+        let stream = &record.dynamodb;
+        SampleRecord {
+            event_id: &record.event_id,
+            event_version: &record.event_version,
+            dynamodb: SampleStreamRecord {
+                keys: sample_item(&stream.keys),
+                new_image: sample_item(&stream.new_image),
+                old_image: sample_item(&stream.old_image),
+                stream_view_type: &stream.stream_view_type,
+                sequence_number: &stream.sequence_number,
+                size_bytes: stream.size_bytes,
+            },
+            aws_region: &record.aws_region,
+            event_name: record.event_name.as_str(),
+            event_source_arn: &record.event_source_arn,
+            event_source: &record.event_source,
+        }
+    }
+}
+
+impl DynamoDBEventBuilder {
+    /// The event as JSON laid out like `sam local generate-event dynamodb
+    /// update`, for `sam local invoke --event` and LocalStack tests. Fields
+    /// follow SAM's order, but attributes are sorted by name and eventVersion
+    /// is the record's, so the text can differ from SAM's own output.
+    pub fn to_sample_json(&self) -> String {
+        let event = self.clone().build();
+        let sample = SampleEvent {
+            records: event.records.iter().map(SampleRecord::from).collect(),
+        };
+        serde_json::to_string_pretty(&sample).expect("sample events serialize")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dynamodb::{DynamoDBEvent, DynamoDBOperationType};
+
+    // The builder for the first record of SAM's dynamodb-update template.
+    fn sam_builder() -> DynamoDBEventBuilder {
+        let mut image = HashMap::new();
+        image.insert("Message".to_string(), AttributeValue::s("New item!"));
+        image.insert("Id".to_string(), AttributeValue::n(101));
+        DynamoDBEvent::builder().record(
+            DynamoDBEventRecord::builder()
+                .event_id("1")
+                .event_name(DynamoDBOperationType::Insert)
+                .aws_region("us-west-2")
+                .table_name("ExampleTableWithStream")
+                .stream_label("2015-06-27T00:48:05.899")
+                .sequence_number("111")
+                .key("Id", AttributeValue::n(101))
+                .new_image(image)
+                .build(),
+        )
+    }
+
+    #[test]
+    fn sam_layout() {
+        let builder = sam_builder();
+        let expected = r#"{
+  "Records": [
+    {
+      "eventID": "1",
+      "eventVersion": "1.1",
+      "dynamodb": {
+        "Keys": {
+          "Id": {
+            "N": "101"
+          }
+        },
+        "NewImage": {
+          "Id": {
+            "N": "101"
+          },
+          "Message": {
+            "S": "New item!"
+          }
+        },
+        "StreamViewType": "NEW_AND_OLD_IMAGES",
+        "SequenceNumber": "111",
+        "SizeBytes": 26
+      },
+      "awsRegion": "us-west-2",
+      "eventName": "INSERT",
+      "eventSourceARN": "arn:aws:dynamodb:us-west-2:123456789012:table/ExampleTableWithStream/stream/2015-06-27T00:48:05.899",
+      "eventSource": "aws:dynamodb"
+    }
+  ]
+}"#;
+        assert_eq!(builder.to_sample_json(), expected);
+        let event: DynamoDBEvent = serde_json::from_str(expected).unwrap();
+        assert_eq!(event.records[0].event_id, "1");
+    }
+
+    // The first record of SAM's dynamodb-update template, with its region and
+    // account placeholders filled in.
+    const SAM_RECORD: &str = r#"{
+      "eventID": "1",
+      "eventVersion": "1.0",
+      "dynamodb": {
+        "Keys": {
+          "Id": {
+            "N": "101"
+          }
+        },
+        "NewImage": {
+          "Message": {
+            "S": "New item!"
+          },
+          "Id": {
+            "N": "101"
+          }
+        },
+        "StreamViewType": "NEW_AND_OLD_IMAGES",
+        "SequenceNumber": "111",
+        "SizeBytes": 26
+      },
+      "awsRegion": "us-west-2",
+      "eventName": "INSERT",
+      "eventSourceARN": "arn:aws:dynamodb:us-west-2:123456789012:table/ExampleTableWithStream/stream/2015-06-27T00:48:05.899",
+      "eventSource": "aws:dynamodb"
+    }"#;
+
+    #[test]
+    fn matches_sam_template_apart_from_documented_differences() {
+        let output: Value = serde_json::from_str(&sam_builder().to_sample_json()).unwrap();
+
+        let mut sam: Value = serde_json::from_str(SAM_RECORD).unwrap();
+        sam["eventVersion"] = "1.1".into();
+        assert_eq!(output["Records"][0], sam);
+    }
+
+    #[test]
+    fn nested_maps_sorted() {
+        let mut nested = HashMap::new();
+        for name in ["Zeta", "Alpha", "Mid"] {
+            nested.insert(name.to_string(), AttributeValue::bool(true));
+        }
+        let builder = DynamoDBEvent::builder().record(
+            DynamoDBEventRecord::builder()
+                .key("Id", AttributeValue::n(1))
+                .key("Nested", AttributeValue::m(nested))
+                .build(),
+        );
+        let json = builder.to_sample_json();
+        let position = |name: &str| json.find(name).unwrap();
+        assert!(position("Alpha") < position("Mid"));
+        assert!(position("Mid") < position("Zeta"));
+    }
+}
@@ -0,0 +1,150 @@
+//! `JsonSchema` implementations for the types with hand-written serde impls,
+//! describing their wire format. The event and record structs derive theirs.
+//! Wire enums accept any string, so their schemas do not list the known
+//! values.
+
+use super::{
+    AttributeValue, DynamoDBKeyType, DynamoDBOperationType, DynamoDBStreamViewType,
+    DynamoDBUserIdentity,
+};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+fn from_json(value: Value) -> Schema {
+    serde_json::from_value(value).expect("schema literals are valid")
+}
+
+macro_rules! string_schema {
+    ($($name:ident),*) => {
+        $(
+            impl JsonSchema for $name {
+                fn schema_name() -> String {
+                    stringify!($name).to_string()
+                }
+
+                fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                    from_json(json!({"type": "string"}))
+                }
+            }
+        )*
+    };
+}
+
+string_schema!(
+    DynamoDBKeyType,
+    DynamoDBOperationType,
+    DynamoDBStreamViewType
+);
+
+impl JsonSchema for DynamoDBUserIdentity {
+    fn schema_name() -> String {
+        "DynamoDBUserIdentity".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        from_json(json!({
+            "type": "object",
+            "required": ["type", "principalId"],
+            "properties": {
+                "type": {"type": "string"},
+                "principalId": {"type": "string"}
+            },
+            "additionalProperties": !cfg!(feature = "strict")
+        }))
+    }
+}
+
+// Binary values are described as base64, the encoding DynamoDB Streams uses;
+// see `WithBinaryEncoding` for the alternative.
+impl JsonSchema for AttributeValue {
+    fn schema_name() -> String {
+        "AttributeValue".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let value = serde_json::to_value(gen.subschema_for::<AttributeValue>())
+            .expect("schemas always serialize");
+        let binary = json!({"type": "string", "contentEncoding": "base64"});
+        let strings = json!({"type": "array", "items": {"type": "string"}});
+        from_json(json!({
+            "type": "object",
+            "minProperties": 1,
+            "maxProperties": 1,
+            "properties": {
+                "B": binary,
+                "BOOL": {"type": "boolean"},
+                "BS": {"type": "array", "items": binary},
+                "L": {"type": "array", "items": value},
+                "M": {"type": "object", "additionalProperties": value},
+                "N": {"type": "string"},
+                "NS": strings,
+                "NULL": {"const": true},
+                "S": {"type": "string"},
+                "SS": strings
+            },
+            "additionalProperties": !cfg!(feature = "strict")
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::DynamoDBEvent;
+    use crate::fixtures::dynamodb::{INSERT_EVENT_JSON, MODIFY_EVENT_JSON, REMOVE_EVENT_JSON};
+    use serde_json::Value;
+
+    // Every field of the fixtures is described by the schema.
+    fn assert_described(value: &Value, schema: &Value, definitions: &Value, path: &str) {
+        if let Some(name) = schema["$ref"].as_str() {
+            let name = name.trim_start_matches("#/definitions/");
+            return assert_described(value, &definitions[name], definitions, path);
+        }
+        // Options are `anyOf` the inner schema and null.
+        if let (Some(options), false) = (schema["anyOf"].as_array(), value.is_null()) {
+            return assert_described(value, &options[0], definitions, path);
+        }
+        match value {
+            Value::Object(map) => {
+                for (name, field) in map {
+                    let field_schema = match &schema["properties"][name] {
+                        Value::Null => &schema["additionalProperties"],
+                        known => known,
+                    };
+                    assert!(
+                        field_schema.is_object(),
+                        "{}.{} is not described",
+                        path,
+                        name
+                    );
+                    let path = format!("{}.{}", path, name);
+                    assert_described(field, field_schema, definitions, &path);
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    assert_described(value, &schema["items"], definitions, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn schema_describes_fixtures() {
+        let schema = serde_json::to_value(schemars::schema_for!(DynamoDBEvent)).unwrap();
+        let definitions = &schema["definitions"];
+        let record = &definitions["DynamoDBEventRecord"];
+        for field in &["dynamodb", "eventID", "eventName"] {
+            assert!(record["required"]
+                .as_array()
+                .unwrap()
+                .contains(&Value::from(*field)));
+        }
+        for fixture in &[INSERT_EVENT_JSON, MODIFY_EVENT_JSON, REMOVE_EVENT_JSON] {
+            let event: Value = serde_json::from_slice(fixture).unwrap();
+            assert_described(&event, &schema, definitions, "");
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Conversions to and from `aws_sdk_dynamodb::types::AttributeValue`.
+
+use super::AttributeValue;
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::AttributeValue as SdkAttributeValue;
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/// Returned when an attribute value has no equivalent on the other side:
+/// an `AttributeValue` with no type field set, or an SDK value of a type
+/// newer than this crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdkConversionError;
+
+impl fmt::Display for SdkConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("attribute value has no known type")
+    }
+}
+
+impl Error for SdkConversionError {}
+
+impl TryFrom<AttributeValue> for SdkAttributeValue {
+    type Error = SdkConversionError;
+
+    fn try_from(value: AttributeValue) -> Result<Self, Self::Error> {
+        Ok(if let Some(s) = value.s {
+            SdkAttributeValue::S(s)
+        } else if let Some(n) = value.n {
+            SdkAttributeValue::N(n)
+        } else if let Some(b) = value.bool {
+            SdkAttributeValue::Bool(b)
+        } else if let Some(null) = value.null {
+            SdkAttributeValue::Null(null)
+        } else if let Some(b) = value.b {
+            SdkAttributeValue::B(Blob::new(b.to_vec()))
+        } else if let Some(m) = value.m {
+            SdkAttributeValue::M(to_sdk_item(m)?)
+        } else if let Some(l) = value.l {
+            SdkAttributeValue::L(
+                l.into_iter()
+                    .map(SdkAttributeValue::try_from)
+                    .collect::<Result<_, _>>()?,
+            )
+        } else if let Some(ss) = value.ss {
+            SdkAttributeValue::Ss(ss)
+        } else if let Some(ns) = value.ns {
+            SdkAttributeValue::Ns(ns)
+        } else if let Some(bs) = value.bs {
+            SdkAttributeValue::Bs(bs.iter().map(|b| Blob::new(b.to_vec())).collect())
+        } else {
+            return Err(SdkConversionError);
+        })
+    }
+}
+
+impl TryFrom<SdkAttributeValue> for AttributeValue {
+    type Error = SdkConversionError;
+
+    fn try_from(value: SdkAttributeValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            SdkAttributeValue::S(s) => AttributeValue::s(s),
+            SdkAttributeValue::N(n) => AttributeValue::n(n),
+            SdkAttributeValue::Bool(b) => AttributeValue::bool(b),
+            SdkAttributeValue::Null(null) => AttributeValue {
+                null: Some(null),
+                ..Default::default()
+            },
+            SdkAttributeValue::B(b) => AttributeValue::b(Bytes::from(b.into_inner())),
+            SdkAttributeValue::M(m) => AttributeValue::m(from_sdk_item(m)?),
+            SdkAttributeValue::L(l) => AttributeValue::l(
+                l.into_iter()
+                    .map(AttributeValue::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+            SdkAttributeValue::Ss(ss) => AttributeValue::ss(ss),
+            SdkAttributeValue::Ns(ns) => AttributeValue::ns(ns),
+            SdkAttributeValue::Bs(bs) => {
+                AttributeValue::bs(bs.into_iter().map(|b| Bytes::from(b.into_inner())))
+            }
+            _ => return Err(SdkConversionError),
+        })
+    }
+}
+
+/// Converts an item (e.g. a stream record's `NewImage`) for use with the SDK.
+pub fn to_sdk_item(
+    item: HashMap<String, AttributeValue>,
+) -> Result<HashMap<String, SdkAttributeValue>, SdkConversionError> {
+    item.into_iter()
+        .map(|(name, value)| Ok((name, SdkAttributeValue::try_from(value)?)))
+        .collect()
+}
+
+/// Converts an item returned by the SDK into this crate's representation.
+pub fn from_sdk_item(
+    item: HashMap<String, SdkAttributeValue>,
+) -> Result<HashMap<String, AttributeValue>, SdkConversionError> {
+    item.into_iter()
+        .map(|(name, value)| Ok((name, AttributeValue::try_from(value)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sdk_roundtrip() {
+        let mut item = HashMap::new();
+        item.insert("Name".to_string(), AttributeValue::s("Joe"));
+        item.insert("Age".to_string(), AttributeValue::n(35));
+        item.insert("Avatar".to_string(), AttributeValue::b(&b"png"[..]));
+        item.insert(
+            "Tags".to_string(),
+            AttributeValue::l(vec![AttributeValue::s("a"), AttributeValue::bool(true)]),
+        );
+
+        let sdk = to_sdk_item(item.clone()).unwrap();
+        assert_eq!(sdk["Age"], SdkAttributeValue::N("35".to_string()));
+        assert_eq!(from_sdk_item(sdk).unwrap(), item);
+        assert_eq!(
+            SdkAttributeValue::try_from(AttributeValue::default()),
+            Err(SdkConversionError)
+        );
+    }
+}
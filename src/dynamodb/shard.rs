@@ -0,0 +1,184 @@
+use super::{compare_sequence_numbers, AttributeValue, DynamoDBEvent, DynamoDBStreamRecord};
+use std::cmp::Ordering;
+
+// FNV-1a, chosen over std's DefaultHasher and Hash impls because its output is
+// stable across Rust releases and platforms, so partition hints can be
+// persisted or shared between services.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Fnv1a {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    // Length-prefixed, so adjacent fields cannot run together.
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    // The type and raw value of an attribute, so binary values hash the same
+    // however they would be serialized. Key attributes are S, N or B, but any
+    // value is accepted.
+    fn write_value(&mut self, value: &AttributeValue) {
+        let scalars: [(&[u8], Option<&[u8]>); 5] = [
+            (b"B", value.b.as_deref()),
+            (
+                b"BOOL",
+                value.bool.map(|b| if b { &b"1"[..] } else { &b"0"[..] }),
+            ),
+            (b"N", value.n.as_deref().map(str::as_bytes)),
+            (b"NULL", value.null.map(|_| &b""[..])),
+            (b"S", value.s.as_deref().map(str::as_bytes)),
+        ];
+        for (name, bytes) in scalars.iter() {
+            if let Some(bytes) = bytes {
+                self.write_field(name);
+                self.write_field(bytes);
+            }
+        }
+        if let Some(members) = &value.bs {
+            self.write_members(b"BS", members.iter().map(|b| &b[..]));
+        }
+        if let Some(members) = &value.ns {
+            self.write_members(b"NS", members.iter().map(String::as_bytes));
+        }
+        if let Some(members) = &value.ss {
+            self.write_members(b"SS", members.iter().map(String::as_bytes));
+        }
+        if let Some(values) = &value.l {
+            self.write_field(b"L");
+            self.write(&(values.len() as u64).to_le_bytes());
+            for value in values {
+                self.write_value(value);
+            }
+        }
+        if let Some(m) = &value.m {
+            let mut entries: Vec<(&String, &AttributeValue)> = m.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            self.write_field(b"M");
+            self.write_entries(entries.into_iter());
+        }
+    }
+
+    fn write_members<'a>(&mut self, name: &[u8], members: impl ExactSizeIterator<Item = &'a [u8]>) {
+        self.write_field(name);
+        self.write(&(members.len() as u64).to_le_bytes());
+        for member in members {
+            self.write_field(member);
+        }
+    }
+
+    // Entries in name order.
+    fn write_entries<'a>(
+        &mut self,
+        entries: impl ExactSizeIterator<Item = (&'a String, &'a AttributeValue)>,
+    ) {
+        self.write(&(entries.len() as u64).to_le_bytes());
+        for (name, value) in entries {
+            self.write_field(name.as_bytes());
+            self.write_value(value);
+        }
+    }
+}
+
+impl DynamoDBStreamRecord {
+    /// A stable partition in `0..partitions` derived from the record's Keys.
+    /// Records for the same item always map to the same partition, so it can
+    /// be used to fan records out to workers without reordering any one item.
+    ///
+    /// # Panics
+    ///
+    /// If `partitions` is zero.
+    pub fn partition_hint(&self, partitions: u32) -> u32 {
+        assert!(partitions > 0, "partitions must be non-zero");
+        let mut hasher = Fnv1a::new();
+        hasher.write_entries(self.key().0.iter());
+        (hasher.0 % u64::from(partitions)) as u32
+    }
+
+    /// The start of the `bucket_seconds` wide window the record was created in,
+    /// as epoch seconds. SequenceNumbers are opaque and only ordered within a
+    /// shard, so this uses ApproximateCreationDateTime rather than decoding them.
+    pub fn creation_time_bucket(&self, bucket_seconds: u64) -> Option<u64> {
+        let created = self.approximate_creation_date_time?;
+        if created < 0.0 || bucket_seconds == 0 {
+            return None;
+        }
+        let created = created as u64;
+        Some(created - created % bucket_seconds)
+    }
+}
+
+impl DynamoDBEvent {
+    /// The lowest and highest SequenceNumber in the batch, e.g. for logging the
+    /// position a batch covered or checkpointing after it has been processed.
+    pub fn sequence_range(&self) -> Option<(&str, &str)> {
+        let mut numbers = self
+            .records
+            .iter()
+            .map(|record| record.dynamodb.sequence_number.as_str());
+        let first = numbers.next()?;
+        Some(numbers.fold((first, first), |(min, max), n| {
+            (
+                if compare_sequence_numbers(n, min) == Ordering::Less {
+                    n
+                } else {
+                    min
+                },
+                if compare_sequence_numbers(n, max) == Ordering::Greater {
+                    n
+                } else {
+                    max
+                },
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+    use std::collections::HashMap;
+
+    #[test]
+    fn shard_metadata() {
+        let mut event = fixtures::dynamodb::insert_event();
+        event
+            .records
+            .extend(fixtures::dynamodb::modify_event().records);
+        event
+            .records
+            .extend(fixtures::dynamodb::remove_event().records);
+        event.records.swap(0, 2);
+        assert_eq!(
+            event.sequence_range(),
+            Some(("13021600000000001596893679", "13021600000000001596893681"))
+        );
+        assert_eq!(DynamoDBEvent { records: vec![] }.sequence_range(), None);
+
+        let hints: Vec<u32> = event
+            .records
+            .iter()
+            .map(|record| record.dynamodb.partition_hint(16))
+            .collect();
+        assert!(hints.iter().all(|&hint| hint == hints[0] && hint < 16));
+
+        let mut binary = HashMap::new();
+        binary.insert("Id".to_string(), AttributeValue::b(&b"id"[..]));
+        binary.insert("Sort".to_string(), AttributeValue::n(1));
+        let binary = DynamoDBStreamRecord::new("1", binary);
+        // Pinned, since hints may be persisted.
+        assert_eq!(binary.partition_hint(1 << 20), 255797);
+
+        let record = &event.records[2].dynamodb;
+        assert_eq!(record.creation_time_bucket(60), Some(1479499740));
+    }
+}
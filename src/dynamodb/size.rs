@@ -0,0 +1,128 @@
+use super::{AttributeValue, DynamoDBEvent, DynamoDBStreamRecord};
+use crate::payload;
+use std::collections::HashMap;
+
+// The bytes `{"Records":[]}` adds around the records of a serialized event.
+const EVENT_OVERHEAD: usize = r#"{"Records":[]}"#.len();
+
+// Follows DynamoDB's item size rules: attribute names count towards the size,
+// numbers take roughly one byte per two significant digits plus one, and
+// lists and maps carry three bytes of overhead plus one per element.
+fn number_size(n: &str) -> usize {
+    let digits = n
+        .split(['e', 'E'])
+        .next()
+        .unwrap_or("")
+        .trim_start_matches(['-', '+'])
+        .replace('.', "");
+    let significant = digits.trim_start_matches('0').trim_end_matches('0').len();
+    significant.div_ceil(2) + 1
+}
+
+fn value_size(value: &AttributeValue) -> usize {
+    if let Some(s) = &value.s {
+        s.len()
+    } else if let Some(n) = &value.n {
+        number_size(n)
+    } else if let Some(b) = &value.b {
+        b.len()
+    } else if value.bool.is_some() || value.null.is_some() {
+        1
+    } else if let Some(ss) = &value.ss {
+        ss.iter().map(String::len).sum()
+    } else if let Some(ns) = &value.ns {
+        ns.iter().map(|n| number_size(n)).sum()
+    } else if let Some(bs) = &value.bs {
+        bs.iter().map(|b| b.len()).sum()
+    } else if let Some(l) = &value.l {
+        3 + l.iter().map(|v| value_size(v) + 1).sum::<usize>()
+    } else if let Some(m) = &value.m {
+        3 + m
+            .iter()
+            .map(|(name, v)| name.len() + value_size(v) + 1)
+            .sum::<usize>()
+    } else {
+        0
+    }
+}
+
+/// The approximate size DynamoDB would account for an item, in bytes.
+pub fn item_size(item: &HashMap<String, AttributeValue>) -> usize {
+    item.iter()
+        .map(|(name, value)| name.len() + value_size(value))
+        .sum()
+}
+
+impl DynamoDBStreamRecord {
+    /// Recomputes the size of the Keys and images from their contents, for
+    /// records whose SizeBytes is missing or no longer accurate (e.g. after the
+    /// images have been modified).
+    pub fn estimated_size(&self) -> u64 {
+        [&self.keys, &self.new_image, &self.old_image]
+            .iter()
+            .filter_map(|item| item.as_ref())
+            .map(|item| item_size(item) as u64)
+            .sum()
+    }
+}
+
+impl DynamoDBEvent {
+    /// Splits the event into events whose serialized JSON is at most `limit`
+    /// bytes, preserving record order. A record larger than `limit` on its own
+    /// is still emitted, alone in its own event.
+    pub fn split_by_size(self, limit: usize) -> Vec<DynamoDBEvent> {
+        // split_records limits the records array; the rest of the event is
+        // the overhead less the array's brackets.
+        let limit = limit.saturating_sub(EVENT_OVERHEAD - 2);
+        payload::split_records(self.records, limit)
+            .expect("stream records always serialize")
+            .into_iter()
+            .map(|records| DynamoDBEvent { records })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn attribute_sizes() {
+        assert_eq!(value_size(&AttributeValue::s("héllo")), 6);
+        assert_eq!(value_size(&AttributeValue::n("-0012.300")), 3);
+        assert_eq!(value_size(&AttributeValue::bool(true)), 1);
+        assert_eq!(
+            value_size(&AttributeValue::l(vec![
+                AttributeValue::s("ab"),
+                AttributeValue::null()
+            ])),
+            3 + 3 + 2
+        );
+
+        let record = &fixtures::dynamodb::insert_event().records[0];
+        // Keys: Timestamp + Username, NewImage adds Message.
+        let keys = "Timestamp".len() + 19 + "Username".len() + 8;
+        let new_image = keys + "Message".len() + 45;
+        assert_eq!(record.dynamodb.estimated_size(), (keys + new_image) as u64);
+    }
+
+    #[test]
+    fn split_by_size() {
+        let record = fixtures::dynamodb::insert_event().records.remove(0);
+        let record_size = serde_json::to_vec(&record).unwrap().len();
+        let event = DynamoDBEvent {
+            records: vec![record.clone(), record.clone(), record],
+        };
+        let whole = serde_json::to_vec(&event).unwrap().len();
+        assert_eq!(event.clone().split_by_size(whole).len(), 1);
+
+        let limit = EVENT_OVERHEAD + 2 * record_size + 1;
+        let parts = event.clone().split_by_size(limit);
+        assert_eq!(parts.len(), 2);
+        assert!(parts
+            .iter()
+            .all(|part| serde_json::to_vec(part).unwrap().len() <= limit));
+        assert_eq!(event.split_by_size(1).len(), 3);
+    }
+}
@@ -0,0 +1,244 @@
+use super::{DynamoDBEvent, DynamoDBEventRecord};
+use crate::error::EventParseError;
+use serde::de::{Error as _, IgnoredAny};
+use serde::Deserialize;
+use std::io::{self, Read};
+
+/// Iterator over the records of a serialized `DynamoDBEvent`, parsing one
+/// record at a time from a reader. Returned by `DynamoDBEvent::records_from_reader`.
+///
+/// Iteration stops after the first error. Fields after `Records` are not read.
+pub struct RecordsReader<R> {
+    reader: PeekReader<R>,
+    state: State,
+    // Index of the next record, to say which one an error is in.
+    index: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    Start,
+    FirstRecord,
+    NextRecord,
+    Done,
+}
+
+// serde_json consumes the reader a byte at a time, but a few tokens here are
+// scanned by hand, which needs one byte of lookahead.
+struct PeekReader<R> {
+    inner: R,
+    peeked: Option<u8>,
+}
+
+impl<R: Read> Read for PeekReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        match self.peeked.take() {
+            Some(byte) => {
+                buf[0] = byte;
+                Ok(1)
+            }
+            None => self.inner.read(buf),
+        }
+    }
+}
+
+impl<R: Read> PeekReader<R> {
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        if self.peeked.is_none() {
+            let mut byte = [0];
+            loop {
+                match self.inner.read(&mut byte) {
+                    Ok(0) => return Ok(None),
+                    Ok(_) => break,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            self.peeked = Some(byte[0]);
+        }
+        Ok(self.peeked)
+    }
+
+    // Skips whitespace and returns the next significant byte without consuming it.
+    fn peek_token(&mut self) -> io::Result<Option<u8>> {
+        while let Some(byte) = self.peek()? {
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+            self.peeked = None;
+        }
+        Ok(None)
+    }
+}
+
+fn syntax_error(expected: &str) -> serde_json::Error {
+    serde_json::Error::custom(format!("expected {} in DynamoDB event", expected))
+}
+
+impl<R: Read> RecordsReader<R> {
+    fn expect(&mut self, token: u8) -> Result<(), serde_json::Error> {
+        match self.reader.peek_token().map_err(serde_json::Error::io)? {
+            Some(byte) if byte == token => {
+                self.reader.peeked = None;
+                Ok(())
+            }
+            _ => Err(syntax_error(&format!("`{}`", token as char))),
+        }
+    }
+
+    fn deserialize<T: for<'de> Deserialize<'de>>(&mut self) -> Result<T, serde_json::Error> {
+        T::deserialize(&mut serde_json::Deserializer::from_reader(&mut self.reader))
+    }
+
+    // Skips a value we are not interested in. Numbers are scanned by hand since
+    // serde_json would read, and then drop, the byte following them.
+    fn skip_value(&mut self) -> Result<(), serde_json::Error> {
+        match self.reader.peek_token().map_err(serde_json::Error::io)? {
+            Some(b'-') | Some(b'0'..=b'9') => {
+                while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') =
+                    self.reader.peek().map_err(serde_json::Error::io)?
+                {
+                    self.reader.peeked = None;
+                }
+                Ok(())
+            }
+            _ => self.deserialize::<IgnoredAny>().map(|_| ()),
+        }
+    }
+
+    // Advances past the top-level fields preceding "Records" and its `[`.
+    // Returns false if the event has no Records.
+    fn seek_records(&mut self) -> Result<bool, serde_json::Error> {
+        self.expect(b'{')?;
+        if self.reader.peek_token().map_err(serde_json::Error::io)? == Some(b'}') {
+            return Ok(false);
+        }
+        loop {
+            let name: String = self.deserialize()?;
+            self.expect(b':')?;
+            if name == "Records" {
+                self.expect(b'[')?;
+                return Ok(true);
+            }
+            self.skip_value()?;
+            match self.reader.peek_token().map_err(serde_json::Error::io)? {
+                Some(b',') => self.reader.peeked = None,
+                Some(b'}') => return Ok(false),
+                _ => return Err(syntax_error("`,` or `}`")),
+            }
+        }
+    }
+
+    fn next_record(&mut self) -> Result<Option<DynamoDBEventRecord>, serde_json::Error> {
+        if self.state == State::Start {
+            self.state = if self.seek_records()? {
+                State::FirstRecord
+            } else {
+                State::Done
+            };
+        }
+        if self.state == State::Done {
+            return Ok(None);
+        }
+        match self.reader.peek_token().map_err(serde_json::Error::io)? {
+            Some(b']') => {
+                self.state = State::Done;
+                return Ok(None);
+            }
+            Some(b',') if self.state == State::NextRecord => self.reader.peeked = None,
+            _ if self.state == State::FirstRecord => {}
+            _ => return Err(syntax_error("`,` or `]`")),
+        }
+        self.state = State::NextRecord;
+        let record = self.deserialize()?;
+        self.index += 1;
+        Ok(Some(record))
+    }
+}
+
+impl<R: Read> Iterator for RecordsReader<R> {
+    type Item = Result<DynamoDBEventRecord, EventParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next_record().map_err(|source| {
+            let path = if self.state == State::Start {
+                String::new()
+            } else {
+                format!("Records[{}]", self.index)
+            };
+            EventParseError::at_path("dynamodb", path, source)
+        });
+        if result.is_err() {
+            self.state = State::Done;
+        }
+        result.transpose()
+    }
+}
+
+impl DynamoDBEvent {
+    /// Parses records one at a time from a serialized event, so processing
+    /// can start before (or without) the whole batch being held in memory.
+    ///
+    /// The reader is read a byte at a time, so wrap unbuffered readers such
+    /// as a `File` or `TcpStream` in a `std::io::BufReader`.
+    pub fn records_from_reader<R: Read>(reader: R) -> RecordsReader<R> {
+        RecordsReader {
+            reader: PeekReader {
+                inner: reader,
+                peeked: None,
+            },
+            state: State::Start,
+            index: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn stream_records() {
+        let mut event = fixtures::dynamodb::insert_event();
+        event
+            .records
+            .extend(fixtures::dynamodb::modify_event().records);
+        event
+            .records
+            .extend(fixtures::dynamodb::remove_event().records);
+        let data = serde_json::to_vec(&event).unwrap();
+
+        let records: Vec<DynamoDBEventRecord> = DynamoDBEvent::records_from_reader(&data[..])
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records, event.records);
+
+        let empty = r#"{ "Count" : 3 , "Tag": "x", "Records" : [ ] }"#;
+        assert_eq!(
+            DynamoDBEvent::records_from_reader(empty.as_bytes()).count(),
+            0
+        );
+        let from_fixture =
+            DynamoDBEvent::records_from_reader(fixtures::dynamodb::INSERT_EVENT_JSON).count();
+        assert_eq!(from_fixture, 1);
+
+        let first = serde_json::to_string(&event.records[0]).unwrap();
+        let data = format!(r#"{{"Records": [{}, {{"eventID": 1}}"#, first);
+        let mut broken = DynamoDBEvent::records_from_reader(data.as_bytes());
+        assert!(broken.next().unwrap().is_ok());
+        match broken.next().unwrap() {
+            Err(err @ EventParseError::Data { .. }) => {
+                assert_eq!(err.module(), "dynamodb");
+                assert!(err
+                    .to_string()
+                    .starts_with("invalid dynamodb event at Records[1]: "));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(broken.next().is_none());
+    }
+}
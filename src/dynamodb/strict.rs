@@ -0,0 +1,185 @@
+use super::ignored::{Ignored, Path, Wrap};
+use super::DynamoDBEvent;
+use crate::error::EventParseError;
+use serde::de::Error as _;
+use serde::Deserialize;
+use std::cell::RefCell;
+
+// Whether `name` is one of the type descriptors of an attribute value, which
+// must not be null.
+fn is_type_descriptor(name: &str) -> bool {
+    matches!(
+        name,
+        "B" | "BOOL" | "BS" | "L" | "M" | "N" | "NS" | "NULL" | "S" | "SS"
+    )
+}
+
+impl DynamoDBEvent {
+    /// Parses an event, failing if it contains any field these types do not
+    /// model. Intended for contract tests that should notice when AWS adds
+    /// fields; regular parsing ignores them. The `strict` feature makes all
+    /// deserialization of the stream types behave this way, except that with
+    /// `extra-fields` too, unknown record fields are captured in `extra`.
+    ///
+    /// Unknown fields are recorded as they are skipped, in the same pass that
+    /// parses the event. A type descriptor set to null, such as `{"S": null}`,
+    /// is reported as an invalid value rather than accepted as unset.
+    ///
+    /// With the `simd` feature the event is parsed with simd-json, like
+    /// `from_slice`, and input that fails is parsed again with serde_json to
+    /// locate the error.
+    pub fn from_slice_strict(data: &[u8]) -> Result<DynamoDBEvent, EventParseError> {
+        let ignored = RefCell::new(Ignored::default());
+        let event = parse_tracked(data, &ignored)?;
+
+        let Ignored { mut unknown, nulls } = ignored.into_inner();
+        // Captured fields are not skipped, so they are found in the `extra` maps.
+        #[cfg(feature = "extra-fields")]
+        for (index, record) in event.records.iter().enumerate() {
+            let path = format!("Records[{}]", index);
+            let record_fields = record.extra.keys().map(|name| format!("{}.{}", path, name));
+            let stream_fields = record
+                .dynamodb
+                .extra
+                .keys()
+                .map(|name| format!("{}.dynamodb.{}", path, name));
+            unknown.extend(record_fields.chain(stream_fields));
+        }
+        unknown.sort();
+
+        let null_descriptor = nulls
+            .into_iter()
+            .find(|path| is_type_descriptor(path.rsplit('.').next().unwrap_or_default()));
+        if let Some(path) = null_descriptor {
+            let name = path.rsplit('.').next().unwrap_or_default().to_string();
+            let source = serde_json::Error::custom(format!(
+                "invalid type: null, expected a value for `{}`",
+                name
+            ));
+            Err(EventParseError::at_path("dynamodb", path, source))
+        } else if unknown.is_empty() {
+            Ok(event)
+        } else {
+            Err(EventParseError::UnknownFields {
+                module: "dynamodb",
+                paths: unknown,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "simd")]
+fn parse_tracked(
+    data: &[u8],
+    ignored: &RefCell<Ignored>,
+) -> Result<DynamoDBEvent, EventParseError> {
+    let mut copy = data.to_vec();
+    if let Ok(mut deserializer) = simd_json::Deserializer::from_slice(&mut copy) {
+        let root = Path::Root;
+        let event = DynamoDBEvent::deserialize(Wrap {
+            inner: &mut deserializer,
+            path: &root,
+            ignored,
+        });
+        if let Ok(event) = event {
+            return Ok(event);
+        }
+    }
+    // Whatever was tracked before the failure is found again.
+    *ignored.borrow_mut() = Ignored::default();
+    parse_tracked_json(data, ignored)
+}
+
+#[cfg(not(feature = "simd"))]
+fn parse_tracked(
+    data: &[u8],
+    ignored: &RefCell<Ignored>,
+) -> Result<DynamoDBEvent, EventParseError> {
+    parse_tracked_json(data, ignored)
+}
+
+fn parse_tracked_json(
+    data: &[u8],
+    ignored: &RefCell<Ignored>,
+) -> Result<DynamoDBEvent, EventParseError> {
+    let root = Path::Root;
+    let mut deserializer = serde_json::Deserializer::from_slice(data);
+    DynamoDBEvent::deserialize(Wrap {
+        inner: &mut deserializer,
+        path: &root,
+        ignored,
+    })
+    .and_then(|event| deserializer.end().map(|()| event))
+    .map_err(|err| EventParseError::new("dynamodb", data, err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::dynamodb::{INSERT_EVENT_JSON, REMOVE_EVENT_JSON};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn strict_parsing() {
+        assert!(DynamoDBEvent::from_slice_strict(INSERT_EVENT_JSON).is_ok());
+        assert!(DynamoDBEvent::from_slice_strict(REMOVE_EVENT_JSON).is_ok());
+
+        let mut value: Value = serde_json::from_slice(INSERT_EVENT_JSON).unwrap();
+        value["Records"][0]["dynamodb"]["NewImage"]["Message"] = json!({"S": null});
+        let data = serde_json::to_vec(&value).unwrap();
+        match DynamoDBEvent::from_slice_strict(&data) {
+            Err(err @ EventParseError::Data { .. }) => {
+                let message = err.to_string();
+                assert!(message.contains("Records[0].dynamodb.NewImage.Message.S"));
+                assert!(message.contains("null"));
+                assert!(!message.contains("unknown"), "{}", message);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Known fields are not reported, however they serialize.
+        let mut value: Value = serde_json::from_slice(INSERT_EVENT_JSON).unwrap();
+        value["Records"][0]["userIdentity"] = Value::Null;
+        value["Records"][0]["dynamodb"]["ApproximateCreationDateTime"] = json!(1.479499740e9);
+        let data = serde_json::to_vec(&value).unwrap();
+        assert!(DynamoDBEvent::from_slice_strict(&data).is_ok());
+
+        // Reported whether plain deserialization drops it, captures it in
+        // `extra`, or rejects it.
+        let mut value: Value = serde_json::from_slice(INSERT_EVENT_JSON).unwrap();
+        value["Records"][0]["recordFormat"] = "application/json".into();
+        let data = serde_json::to_vec(&value).unwrap();
+        match DynamoDBEvent::from_slice_strict(&data) {
+            Err(EventParseError::UnknownFields { paths, .. }) => {
+                assert_eq!(paths, vec!["Records[0].recordFormat"])
+            }
+            Err(EventParseError::Data { .. })
+                if cfg!(all(feature = "strict", not(feature = "extra-fields"))) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+
+        value["Records"][0]["dynamodb"]["Keys"]["Username"]["X"] = true.into();
+        value["Records"][0]["dynamodb"]["NewImage"]["Tags"] =
+            json!({"L": [{"S": "a"}, {"S": "b", "Y": {"Z": null}}]});
+        let data = serde_json::to_vec(&value).unwrap();
+        if cfg!(feature = "strict") {
+            // Plain deserialization already rejects the unknown fields.
+            assert!(matches!(
+                DynamoDBEvent::from_slice_strict(&data),
+                Err(EventParseError::Data { .. })
+            ));
+            return;
+        }
+        match DynamoDBEvent::from_slice_strict(&data) {
+            Err(EventParseError::UnknownFields { paths, .. }) => assert_eq!(
+                paths,
+                vec![
+                    "Records[0].dynamodb.Keys.Username.X",
+                    "Records[0].dynamodb.NewImage.Tags.L[1].Y",
+                    "Records[0].recordFormat"
+                ]
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::{DynamoDBEventRecord, DynamoDBOperationType, DynamoDBUserIdentity};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const SERVICE: &str = "Service";
+const DYNAMODB_PRINCIPAL: &str = "dynamodb.amazonaws.com";
+
+// The wire format: `{"type": "Service", "principalId": "dynamodb.amazonaws.com"}`.
+#[derive(Serialize)]
+struct RawUserIdentity<'a> {
+    #[serde(rename = "type")]
+    r#type: &'a str,
+    #[serde(rename = "principalId")]
+    principal_id: &'a str,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+struct OwnedUserIdentity {
+    #[serde(rename = "type")]
+    r#type: String,
+    #[serde(rename = "principalId")]
+    principal_id: String,
+}
+
+impl DynamoDBUserIdentity {
+    pub fn r#type(&self) -> &str {
+        match self {
+            DynamoDBUserIdentity::Service { .. } => SERVICE,
+            DynamoDBUserIdentity::Other { r#type, .. } => r#type,
+        }
+    }
+
+    pub fn principal_id(&self) -> &str {
+        match self {
+            DynamoDBUserIdentity::Service { principal_id }
+            | DynamoDBUserIdentity::Other { principal_id, .. } => principal_id,
+        }
+    }
+}
+
+impl Serialize for DynamoDBUserIdentity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawUserIdentity {
+            r#type: self.r#type(),
+            principal_id: self.principal_id(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DynamoDBUserIdentity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let OwnedUserIdentity {
+            r#type,
+            principal_id,
+        } = OwnedUserIdentity::deserialize(deserializer)?;
+        Ok(if r#type == SERVICE {
+            DynamoDBUserIdentity::Service { principal_id }
+        } else {
+            DynamoDBUserIdentity::Other {
+                r#type,
+                principal_id,
+            }
+        })
+    }
+}
+
+impl DynamoDBEventRecord {
+    /// Whether this is a REMOVE performed by DynamoDB's Time to Live process
+    /// rather than by an application.
+    pub fn is_ttl_delete(&self) -> bool {
+        self.event_name == DynamoDBOperationType::Remove
+            && matches!(
+                &self.user_identity,
+                Some(DynamoDBUserIdentity::Service { principal_id })
+                    if principal_id == DYNAMODB_PRINCIPAL
+            )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn user_identity_variants() {
+        let remove = fixtures::dynamodb::remove_event();
+        assert!(remove.records[0].is_ttl_delete());
+        assert!(!fixtures::dynamodb::insert_event().records[0].is_ttl_delete());
+
+        let other: DynamoDBUserIdentity =
+            serde_json::from_str(r#"{"type": "User", "principalId": "AIDA123"}"#).unwrap();
+        assert_eq!(
+            other,
+            DynamoDBUserIdentity::Other {
+                r#type: "User".to_string(),
+                principal_id: "AIDA123".to_string(),
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&other).unwrap(),
+            r#"{"type":"User","principalId":"AIDA123"}"#
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use super::{AttributePath, AttributeValue, DynamoDBEvent, PathSegment};
+use crate::error::{self, EventParseError};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// Describes the first malformed attribute value found, and where it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub path: AttributePath,
+    pub kind: ValidationErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationErrorKind {
+    // None of the type fields are set.
+    NoType,
+    // More than one type field is set, e.g. both "S" and "N".
+    MultipleTypes(Vec<&'static str>),
+    // An "SS", "NS" or "BS" set with no members, which DynamoDB does not allow.
+    EmptySet,
+    // "NULL": false, which DynamoDB does not allow; a null is always true.
+    FalseNull,
+    // An "N" or "NS" member which is not a number.
+    InvalidNumber(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid attribute value at {}: ", self.path)?;
+        match &self.kind {
+            ValidationErrorKind::NoType => write!(f, "no type set"),
+            ValidationErrorKind::MultipleTypes(types) => {
+                write!(f, "multiple types set ({})", types.join(", "))
+            }
+            ValidationErrorKind::EmptySet => write!(f, "empty set"),
+            ValidationErrorKind::FalseNull => write!(f, "NULL is false"),
+            ValidationErrorKind::InvalidNumber(n) => write!(f, "{:?} is not a number", n),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+impl AttributeValue {
+    /// Checks that exactly one type is set, NULL is true, sets are non-empty,
+    /// and numbers are numeric, recursing into lists and maps.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validate_value(&AttributePath::default(), self)
+    }
+}
+
+/// Validates every attribute of an item; see `AttributeValue::validate`.
+pub fn validate_item(item: &HashMap<String, AttributeValue>) -> Result<(), ValidationError> {
+    validate_map(&AttributePath::default(), item)
+}
+
+impl DynamoDBEvent {
+    /// Validates the Keys, NewImage and OldImage of every record.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let records = AttributePath(vec![PathSegment::Attribute("Records".to_string())]);
+        for (index, record) in self.records.iter().enumerate() {
+            let path = records
+                .child(PathSegment::Index(index))
+                .child(PathSegment::Attribute("dynamodb".to_string()));
+            let images = [
+                ("Keys", &record.dynamodb.keys),
+                ("NewImage", &record.dynamodb.new_image),
+                ("OldImage", &record.dynamodb.old_image),
+            ];
+            for (name, image) in images.iter() {
+                if let Some(image) = image {
+                    validate_map(&path.child(PathSegment::Attribute(name.to_string())), image)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses an event and validates it, so malformed attribute values are
+    /// reported up front rather than when first accessed.
+    pub fn from_slice_validated(data: &[u8]) -> Result<DynamoDBEvent, EventParseError> {
+        let event: DynamoDBEvent = error::from_slice_owned("dynamodb", data)?;
+        event
+            .validate()
+            .map_err(|source| EventParseError::Invalid {
+                module: "dynamodb",
+                source,
+            })?;
+        Ok(event)
+    }
+}
+
+fn validate_map(
+    path: &AttributePath,
+    map: &HashMap<String, AttributeValue>,
+) -> Result<(), ValidationError> {
+    let mut names: Vec<&String> = map.keys().collect();
+    names.sort();
+    for name in names {
+        validate_value(
+            &path.child(PathSegment::Attribute(name.clone())),
+            &map[name],
+        )?;
+    }
+    Ok(())
+}
+
+fn validate_value(path: &AttributePath, value: &AttributeValue) -> Result<(), ValidationError> {
+    let error = |kind| {
+        Err(ValidationError {
+            path: path.clone(),
+            kind,
+        })
+    };
+    let types: Vec<&'static str> = [
+        ("B", value.b.is_some()),
+        ("BOOL", value.bool.is_some()),
+        ("BS", value.bs.is_some()),
+        ("L", value.l.is_some()),
+        ("M", value.m.is_some()),
+        ("N", value.n.is_some()),
+        ("NS", value.ns.is_some()),
+        ("NULL", value.null.is_some()),
+        ("S", value.s.is_some()),
+        ("SS", value.ss.is_some()),
+    ]
+    .iter()
+    .filter(|(_, set)| *set)
+    .map(|(name, _)| *name)
+    .collect();
+    match types.len() {
+        0 => return error(ValidationErrorKind::NoType),
+        1 => {}
+        _ => return error(ValidationErrorKind::MultipleTypes(types)),
+    }
+
+    let set_len = value
+        .ss
+        .as_ref()
+        .map(Vec::len)
+        .or_else(|| value.ns.as_ref().map(Vec::len))
+        .or_else(|| value.bs.as_ref().map(Vec::len));
+    if set_len == Some(0) {
+        return error(ValidationErrorKind::EmptySet);
+    }
+    if value.null == Some(false) {
+        return error(ValidationErrorKind::FalseNull);
+    }
+    for n in value.n.iter().chain(value.ns.iter().flatten()) {
+        if !is_number(n) {
+            return error(ValidationErrorKind::InvalidNumber(n.clone()));
+        }
+    }
+    if let Some(m) = &value.m {
+        validate_map(path, m)?;
+    }
+    if let Some(l) = &value.l {
+        for (index, value) in l.iter().enumerate() {
+            validate_value(&path.child(PathSegment::Index(index)), value)?;
+        }
+    }
+    Ok(())
+}
+
+// Matches DynamoDB's number syntax: an optionally signed decimal with an
+// optional exponent, e.g. "-1", "3.14", ".5", "1E+10".
+fn is_number(n: &str) -> bool {
+    let n = n.strip_prefix(['-', '+']).unwrap_or(n);
+    let (mantissa, exponent) = match n.find(['e', 'E']) {
+        Some(i) => (&n[..i], Some(&n[i + 1..])),
+        None => (n, None),
+    };
+    let mut parts = mantissa.splitn(2, '.');
+    let integer = parts.next().unwrap_or("");
+    let fraction = parts.next().unwrap_or("");
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let mantissa_ok =
+        !(integer.is_empty() && fraction.is_empty()) && digits(integer) && digits(fraction);
+    let exponent_ok = exponent.is_none_or(|e| {
+        let e = e.strip_prefix(['-', '+']).unwrap_or(e);
+        !e.is_empty() && digits(e)
+    });
+    mantissa_ok && exponent_ok
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate_attribute_values() {
+        assert!(AttributeValue::s("x").validate().is_ok());
+        assert!(AttributeValue::n("-1.5E+3").validate().is_ok());
+        assert_eq!(
+            AttributeValue::default().validate().unwrap_err().kind,
+            ValidationErrorKind::NoType
+        );
+        assert_eq!(
+            AttributeValue::ss(Vec::<String>::new())
+                .validate()
+                .unwrap_err()
+                .kind,
+            ValidationErrorKind::EmptySet
+        );
+
+        let false_null: AttributeValue = serde_json::from_str(r#"{"NULL": false}"#).unwrap();
+        assert_eq!(
+            false_null.validate().unwrap_err().kind,
+            ValidationErrorKind::FalseNull
+        );
+        assert!(AttributeValue::null().validate().is_ok());
+
+        let mut both = AttributeValue::s("x");
+        both.n = Some("1".to_string());
+        assert_eq!(
+            both.validate().unwrap_err().kind,
+            ValidationErrorKind::MultipleTypes(vec!["N", "S"])
+        );
+
+        let nested = AttributeValue::l(vec![AttributeValue::s("ok"), AttributeValue::n("1x")]);
+        let mut item = HashMap::new();
+        item.insert("Scores".to_string(), nested);
+        let err = validate_item(&item).unwrap_err();
+        assert_eq!(err.path.to_string(), "Scores[1]");
+        assert_eq!(
+            err.to_string(),
+            "invalid attribute value at Scores[1]: \"1x\" is not a number"
+        );
+    }
+
+    #[test]
+    fn validated_event() {
+        let data = include_bytes!("../fixtures/example-dynamo-stream-event.json");
+        assert!(DynamoDBEvent::from_slice_validated(data).is_ok());
+
+        let invalid =
+            String::from_utf8_lossy(data).replace(r#""S": "John Doe""#, r#""N": "John Doe""#);
+        match DynamoDBEvent::from_slice_validated(invalid.as_bytes()) {
+            Err(EventParseError::Invalid { source, .. }) => {
+                assert_eq!(source.path.to_string(), "Records[0].dynamodb.Keys.Username")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! CloudWatch Embedded Metric Format: JSON log lines that CloudWatch Logs
+//! turns into metrics, so a handler can emit metrics by printing them.
+//!
+//! ```
+//! use aws_lambda_events_extended::emf::{MetricLogBuilder, Unit};
+//!
+//! let line = MetricLogBuilder::new("Orders")
+//!     .dimension("Table", "orders")
+//!     .metric("Processed", 3.0, Unit::Count)
+//!     .to_json()
+//!     .unwrap();
+//! println!("{}", line);
+//! ```
+
+use crate::platform;
+use crate::summary::EventSummary;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The most dimensions a metric can have.
+pub const MAX_DIMENSIONS: usize = 30;
+
+/// The most metrics one log line can define.
+pub const MAX_METRICS: usize = 100;
+
+/// The units CloudWatch accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Unit {
+    Seconds,
+    Microseconds,
+    Milliseconds,
+    Bytes,
+    Kilobytes,
+    Megabytes,
+    Gigabytes,
+    Terabytes,
+    Bits,
+    Kilobits,
+    Megabits,
+    Gigabits,
+    Terabits,
+    Percent,
+    Count,
+    #[serde(rename = "Bytes/Second")]
+    BytesPerSecond,
+    #[serde(rename = "Kilobytes/Second")]
+    KilobytesPerSecond,
+    #[serde(rename = "Megabytes/Second")]
+    MegabytesPerSecond,
+    #[serde(rename = "Gigabytes/Second")]
+    GigabytesPerSecond,
+    #[serde(rename = "Terabytes/Second")]
+    TerabytesPerSecond,
+    #[serde(rename = "Bits/Second")]
+    BitsPerSecond,
+    #[serde(rename = "Kilobits/Second")]
+    KilobitsPerSecond,
+    #[serde(rename = "Megabits/Second")]
+    MegabitsPerSecond,
+    #[serde(rename = "Gigabits/Second")]
+    GigabitsPerSecond,
+    #[serde(rename = "Terabits/Second")]
+    TerabitsPerSecond,
+    #[serde(rename = "Count/Second")]
+    CountPerSecond,
+    None,
+}
+
+/// Returned by `MetricLogBuilder::build` for a log line CloudWatch would
+/// reject or ignore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmfError {
+    NoMetrics,
+    TooManyMetrics(usize),
+    TooManyDimensions(usize),
+    /// The named metric's value is NaN or infinite, which JSON cannot hold.
+    InvalidValue(String),
+    /// No timestamp was set and the target has no clock.
+    NoTimestamp,
+}
+
+impl fmt::Display for EmfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmfError::NoMetrics => write!(f, "no metrics to emit"),
+            EmfError::TooManyMetrics(count) => {
+                write!(f, "{} metrics, at most {} allowed", count, MAX_METRICS)
+            }
+            EmfError::TooManyDimensions(count) => {
+                write!(
+                    f,
+                    "{} dimensions, at most {} allowed",
+                    count, MAX_DIMENSIONS
+                )
+            }
+            EmfError::InvalidValue(name) => write!(f, "metric {} is not a finite number", name),
+            EmfError::NoTimestamp => write!(f, "no timestamp"),
+        }
+    }
+}
+
+impl Error for EmfError {}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Metric {
+    name: String,
+    value: f64,
+    unit: Unit,
+}
+
+/// Builds one EMF log line: its metrics share a namespace, a timestamp and a
+/// single set of dimensions. Setting a dimension or metric twice keeps the
+/// last value. Properties are logged alongside without becoming metrics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricLogBuilder {
+    namespace: String,
+    dimensions: Vec<(String, String)>,
+    metrics: Vec<Metric>,
+    properties: Map<String, Value>,
+    timestamp: Option<SystemTime>,
+}
+
+impl MetricLogBuilder {
+    pub fn new<S: Into<String>>(namespace: S) -> Self {
+        MetricLogBuilder {
+            namespace: namespace.into(),
+            dimensions: Vec::new(),
+            metrics: Vec::new(),
+            properties: Map::new(),
+            timestamp: None,
+        }
+    }
+
+    pub fn dimension<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        let name = name.into();
+        self.dimensions.retain(|(existing, _)| *existing != name);
+        self.dimensions.push((name, value.into()));
+        self
+    }
+
+    pub fn metric<N: Into<String>>(mut self, name: N, value: f64, unit: Unit) -> Self {
+        let name = name.into();
+        self.metrics.retain(|metric| metric.name != name);
+        self.metrics.push(Metric { name, value, unit });
+        self
+    }
+
+    pub fn property<N: Into<String>>(mut self, name: N, value: Value) -> Self {
+        self.properties.insert(name.into(), value);
+        self
+    }
+
+    /// When the metrics were observed. Defaults to when the line is built.
+    pub fn timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Adds an event summary's numbers as `RecordCount`, `PayloadBytes` and,
+    /// when known, `OldestRecordAge`, with the source as the `EventSource`
+    /// dimension.
+    pub fn event_summary(self, summary: &EventSummary) -> Self {
+        let builder = self
+            .dimension("EventSource", summary.source.as_str())
+            .metric("RecordCount", summary.record_count as f64, Unit::Count)
+            .metric("PayloadBytes", summary.payload_bytes as f64, Unit::Bytes);
+        match summary.oldest_record_age {
+            Some(age) => builder.metric(
+                "OldestRecordAge",
+                age.as_millis() as f64,
+                Unit::Milliseconds,
+            ),
+            None => builder,
+        }
+    }
+
+    pub fn build(&self) -> Result<Value, EmfError> {
+        if self.metrics.is_empty() {
+            return Err(EmfError::NoMetrics);
+        }
+        if self.metrics.len() > MAX_METRICS {
+            return Err(EmfError::TooManyMetrics(self.metrics.len()));
+        }
+        if self.dimensions.len() > MAX_DIMENSIONS {
+            return Err(EmfError::TooManyDimensions(self.dimensions.len()));
+        }
+        if let Some(metric) = self.metrics.iter().find(|metric| !metric.value.is_finite()) {
+            return Err(EmfError::InvalidValue(metric.name.clone()));
+        }
+        let timestamp = self
+            .timestamp
+            .or_else(platform::now)
+            .ok_or(EmfError::NoTimestamp)?;
+        let millis = timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        // Metrics and dimensions are looked up by name among the line's
+        // top-level members, so properties are written first and can't
+        // shadow them.
+        let mut line = self.properties.clone();
+        let dimension_names: Vec<&str> = self
+            .dimensions
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let metric_definitions: Vec<Value> = self
+            .metrics
+            .iter()
+            .map(|metric| json!({"Name": metric.name, "Unit": metric.unit}))
+            .collect();
+        line.insert(
+            "_aws".to_string(),
+            json!({
+                "Timestamp": millis,
+                "CloudWatchMetrics": [{
+                    "Namespace": self.namespace,
+                    "Dimensions": [dimension_names],
+                    "Metrics": metric_definitions,
+                }],
+            }),
+        );
+        for (name, value) in &self.dimensions {
+            line.insert(name.clone(), Value::from(value.as_str()));
+        }
+        for metric in &self.metrics {
+            line.insert(metric.name.clone(), Value::from(metric.value));
+        }
+        Ok(Value::Object(line))
+    }
+
+    /// The log line as compact JSON, ready to print.
+    pub fn to_json(&self) -> Result<String, EmfError> {
+        Ok(self.build()?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn log_line() {
+        let line = MetricLogBuilder::new("Orders")
+            .timestamp(UNIX_EPOCH + Duration::from_millis(1_700_000_000_123))
+            .dimension("Table", "old")
+            .dimension("Table", "orders")
+            .dimension("Stage", "prod")
+            .metric("Latency", 12.5, Unit::Milliseconds)
+            .metric("Throughput", 2.0, Unit::BytesPerSecond)
+            .property("requestId", json!("abc"))
+            .property("Table", json!("shadowed"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            line,
+            json!({
+                "_aws": {
+                    "Timestamp": 1_700_000_000_123u64,
+                    "CloudWatchMetrics": [{
+                        "Namespace": "Orders",
+                        "Dimensions": [["Table", "Stage"]],
+                        "Metrics": [
+                            {"Name": "Latency", "Unit": "Milliseconds"},
+                            {"Name": "Throughput", "Unit": "Bytes/Second"}
+                        ]
+                    }]
+                },
+                "Table": "orders",
+                "Stage": "prod",
+                "Latency": 12.5,
+                "Throughput": 2.0,
+                "requestId": "abc"
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let builder = MetricLogBuilder::new("Orders");
+        assert_eq!(builder.build(), Err(EmfError::NoMetrics));
+
+        let builder = (0..=MAX_METRICS).fold(builder, |builder, i| {
+            builder.metric(format!("m{}", i), 1.0, Unit::Count)
+        });
+        assert_eq!(
+            builder.to_json(),
+            Err(EmfError::TooManyMetrics(MAX_METRICS + 1))
+        );
+
+        let builder = (0..=MAX_DIMENSIONS).fold(
+            MetricLogBuilder::new("Orders").metric("m", 1.0, Unit::None),
+            |builder, i| builder.dimension(format!("d{}", i), "v"),
+        );
+        assert_eq!(
+            builder.build().unwrap_err().to_string(),
+            "31 dimensions, at most 30 allowed"
+        );
+
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let builder = MetricLogBuilder::new("Orders")
+                .metric("Latency", 1.0, Unit::Milliseconds)
+                .metric("Ratio", value, Unit::None);
+            assert_eq!(
+                builder.build(),
+                Err(EmfError::InvalidValue("Ratio".to_string()))
+            );
+        }
+        assert_eq!(
+            EmfError::InvalidValue("Ratio".to_string()).to_string(),
+            "metric Ratio is not a finite number"
+        );
+    }
+
+    #[test]
+    fn event_summaries() {
+        let summary = EventSummary {
+            source: "aws:dynamodb".to_string(),
+            record_count: 2,
+            payload_bytes: 512,
+            oldest_record_age: Some(Duration::from_secs(3)),
+        };
+        let line = MetricLogBuilder::new("Streams")
+            .event_summary(&summary)
+            .build()
+            .unwrap();
+        assert_eq!(line["EventSource"], "aws:dynamodb");
+        assert_eq!(line["RecordCount"], 2.0);
+        assert_eq!(line["PayloadBytes"], 512.0);
+        assert_eq!(line["OldestRecordAge"], 3000.0);
+        assert!(line["_aws"]["Timestamp"].as_u64().unwrap() > 0);
+    }
+}
@@ -0,0 +1,6 @@
+//! Serde adapters for the encodings AWS uses for binary payloads inside JSON
+//! events, for reuse in your own detail and payload types.
+
+pub mod base64;
+#[cfg(feature = "flate2")]
+pub mod compressed;
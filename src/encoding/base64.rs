@@ -0,0 +1,435 @@
+//! Standard (RFC 4648) base64 handling for binary payloads, which AWS
+//! delivers as base64 strings inside the JSON event.
+//!
+//! The `bytes`, `vec`, `option` and `option_vec` modules are
+//! `#[serde(with = "...")]` adapters. Deserialization also accepts raw byte
+//! arrays, and `WithBinaryEncoding` serializes values as raw bytes instead.
+//!
+//! Events roundtrip through binary formats such as MessagePack and CBOR, with
+//! binary values stored as raw bytes, as long as structs are encoded as maps:
+//! optional fields are skipped when empty, so positional encodings like
+//! `rmp_serde::to_vec` cannot be read back. Use `rmp_serde::to_vec_named`.
+
+use ::bytes::Bytes;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PAD: u8 = b'=';
+
+/// Encodes `input` as padded base64.
+pub fn encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                output.push(PAD as char);
+            }
+        }
+    }
+    output
+}
+
+/// Decodes padded or unpadded base64.
+pub fn decode(input: &str) -> Result<Vec<u8>, DecodeError> {
+    let input = input.as_bytes();
+    let unpadded = match input {
+        [rest @ .., PAD, PAD] | [rest @ .., PAD] => rest,
+        _ => input,
+    };
+    if unpadded.len() % 4 == 1 || (unpadded.len() != input.len() && input.len() % 4 != 0) {
+        return Err(DecodeError::InvalidLength);
+    }
+    let mut output = Vec::with_capacity(unpadded.len() * 3 / 4);
+    for chunk in unpadded.chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            n |= u32::from(sextet(c)?) << (18 - 6 * i);
+        }
+        output.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    Ok(output)
+}
+
+fn sextet(c: u8) -> Result<u8, DecodeError> {
+    match c {
+        b'A'..=b'Z' => Ok(c - b'A'),
+        b'a'..=b'z' => Ok(c - b'a' + 26),
+        b'0'..=b'9' => Ok(c - b'0' + 52),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(DecodeError::InvalidByte(c)),
+    }
+}
+
+/// Returned when a string is not valid base64.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    InvalidByte(u8),
+    InvalidLength,
+}
+
+impl Error for DecodeError {}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidByte(c) => write!(f, "invalid base64 byte {:?}", *c as char),
+            DecodeError::InvalidLength => write!(f, "invalid base64 length"),
+        }
+    }
+}
+
+/// How binary values, such as `B` and `BS` attribute values, are written
+/// when serializing; see `WithBinaryEncoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    /// Base64 strings, as AWS delivers them. The default.
+    Base64,
+    /// Raw bytes, i.e. arrays of numbers in JSON, for consumers outside AWS.
+    Bytes,
+}
+
+impl BinaryEncoding {
+    /// Serializes `bytes` in this encoding. Formats that are not
+    /// human-readable, such as MessagePack or CBOR, always get raw bytes.
+    pub fn serialize<S: Serializer>(self, bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            BinaryEncoding::Base64 => serialize_binary(bytes, serializer),
+            BinaryEncoding::Bytes => serializer.serialize_bytes(bytes),
+        }
+    }
+}
+
+/// Serializes `value` with its binary values, such as `B` and `BS` attribute
+/// values, written in `encoding`:
+///
+/// ```
+/// use aws_lambda_events_extended::encoding::base64::{BinaryEncoding, WithBinaryEncoding};
+///
+/// let raw = serde_json::to_string(&WithBinaryEncoding::new(&b"ab"[..], BinaryEncoding::Bytes));
+/// assert_eq!(raw.unwrap(), "[97,98]");
+/// ```
+///
+/// Implemented for byte slices and `Bytes`, and with the `dynamodb` feature
+/// for `AttributeValue`, lists of them, items, and whole events, records and
+/// stream records. Deserialization accepts either form regardless.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithBinaryEncoding<T> {
+    pub value: T,
+    pub encoding: BinaryEncoding,
+}
+
+impl<T> WithBinaryEncoding<T> {
+    pub fn new(value: T, encoding: BinaryEncoding) -> WithBinaryEncoding<T> {
+        WithBinaryEncoding { value, encoding }
+    }
+}
+
+impl Serialize for WithBinaryEncoding<&[u8]> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.encoding.serialize(self.value, serializer)
+    }
+}
+
+impl Serialize for WithBinaryEncoding<&Bytes> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.encoding.serialize(self.value, serializer)
+    }
+}
+
+// Binary formats such as MessagePack and CBOR store bytes natively, so only
+// human-readable ones get base64.
+fn serialize_binary<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+// A binary value in either representation: a base64 string or raw bytes.
+struct Binary(Vec<u8>);
+
+impl<'de> Deserialize<'de> for Binary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Binary, D::Error> {
+        struct BinaryVisitor;
+
+        impl<'de> Visitor<'de> for BinaryVisitor {
+            type Value = Binary;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a base64 string or a byte array")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Binary, E> {
+                decode(value).map(Binary).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Binary, E> {
+                Ok(Binary(value.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Binary, E> {
+                Ok(Binary(value))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Binary, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(Binary(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(BinaryVisitor)
+    }
+}
+
+/// `#[serde(with = "...")]` adapter for `Bytes`.
+pub mod bytes {
+    use super::Binary;
+    use bytes::Bytes;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize_binary(value, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+        Ok(Bytes::from(Binary::deserialize(deserializer)?.0))
+    }
+}
+
+/// `#[serde(with = "...")]` adapter for `Vec<u8>`.
+pub mod vec {
+    use super::Binary;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize_binary(value, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        Ok(Binary::deserialize(deserializer)?.0)
+    }
+}
+
+/// `#[serde(with = "...")]` adapter for `Option<Bytes>`.
+pub mod option {
+    use super::Binary;
+    use bytes::Bytes;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    struct Value<'a>(&'a [u8]);
+
+    impl Serialize for Value<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize_binary(self.0, serializer)
+        }
+    }
+
+    pub fn serialize<S>(value: &Option<Bytes>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(bytes) => Value(bytes).serialize(serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Bytes>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<Binary>::deserialize(deserializer)?.map(|binary| Bytes::from(binary.0)))
+    }
+}
+
+/// `#[serde(with = "...")]` adapter for `Option<Vec<Bytes>>`.
+pub mod option_vec {
+    use super::Binary;
+    use bytes::Bytes;
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    struct Value<'a>(&'a [u8]);
+
+    impl Serialize for Value<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::serialize_binary(self.0, serializer)
+        }
+    }
+
+    pub fn serialize<S>(value: &Option<Vec<Bytes>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for bytes in values {
+                    seq.serialize_element(&Value(bytes))?;
+                }
+                seq.end()
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Vec<Bytes>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let values = Option::<Vec<Binary>>::deserialize(deserializer)?;
+        Ok(values.map(|values| {
+            values
+                .into_iter()
+                .map(|binary| Bytes::from(binary.0))
+                .collect()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        for (raw, encoded) in &[
+            (&b""[..], ""),
+            (&b"f"[..], "Zg=="),
+            (&b"fo"[..], "Zm8="),
+            (&b"foo"[..], "Zm9v"),
+            (&b"foobar"[..], "Zm9vYmFy"),
+        ] {
+            assert_eq!(encode(raw), *encoded);
+            assert_eq!(decode(encoded).unwrap(), *raw);
+        }
+        assert_eq!(decode("Zm8").unwrap(), b"fo");
+        assert_eq!(decode("Zm9!"), Err(DecodeError::InvalidByte(b'!')));
+        assert_eq!(decode("Zm9vY"), Err(DecodeError::InvalidLength));
+    }
+
+    #[test]
+    fn adapters() {
+        use ::bytes::Bytes;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Payload {
+            #[serde(with = "super::bytes")]
+            bytes: Bytes,
+            #[serde(with = "super::vec")]
+            vec: Vec<u8>,
+            #[serde(with = "super::option")]
+            option: Option<Bytes>,
+        }
+
+        let json = r#"{"bytes":"YWI=","vec":"Yw==","option":null}"#;
+        let payload: Payload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.bytes, Bytes::from(&b"ab"[..]));
+        assert_eq!(payload.vec, b"c");
+        assert_eq!(serde_json::to_string(&payload).unwrap(), json);
+        assert!(
+            serde_json::from_str::<Payload>(r#"{"bytes":"!","vec":"","option":null}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn binary_inputs() {
+        use serde::de::value::{BytesDeserializer, Error, SeqDeserializer};
+
+        // What binary formats such as MessagePack and CBOR hand the visitor.
+        let bytes = Binary::deserialize(BytesDeserializer::<Error>::new(b"ab")).unwrap();
+        assert_eq!(bytes.0, b"ab");
+        let seq = SeqDeserializer::<_, Error>::new(vec![97u8, 98].into_iter());
+        assert_eq!(Binary::deserialize(seq).unwrap().0, b"ab");
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn binary_encodings() {
+        use crate::dynamodb::AttributeValue;
+
+        let value = AttributeValue::bs(vec![&b"ab"[..], &b"c"[..]]);
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, r#"{"BS":["YWI=","Yw=="]}"#);
+
+        let value = AttributeValue::l(vec![AttributeValue::b(&b"ab"[..]), value]);
+        let raw = serde_json::to_string(&WithBinaryEncoding::new(&value, BinaryEncoding::Bytes));
+        assert_eq!(
+            raw.unwrap(),
+            r#"{"L":[{"B":[97,98]},{"BS":[[97,98],[99]]}]}"#
+        );
+        let base64 = WithBinaryEncoding::new(&value, BinaryEncoding::Base64);
+        assert_eq!(
+            serde_json::to_string(&base64).unwrap(),
+            r#"{"L":[{"B":"YWI="},{"BS":["YWI=","Yw=="]}]}"#
+        );
+
+        let raw = serde_json::to_string(&WithBinaryEncoding::new(&value, BinaryEncoding::Bytes));
+        let parsed: AttributeValue = serde_json::from_str(&raw.unwrap()).unwrap();
+        assert_eq!(parsed, value);
+
+        let mut item = std::collections::HashMap::new();
+        item.insert("Avatar".to_string(), AttributeValue::b(&b"c"[..]));
+        let raw = serde_json::to_string(&WithBinaryEncoding::new(&item, BinaryEncoding::Bytes));
+        assert_eq!(raw.unwrap(), r#"{"Avatar":{"B":[99]}}"#);
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn binary_formats() {
+        use crate::dynamodb::{AttributeValue, DynamoDBEvent, DynamoDBEventRecord};
+        use std::collections::HashMap;
+
+        let mut image = HashMap::new();
+        image.insert("Avatar".to_string(), AttributeValue::b(&b"\x00png"[..]));
+        image.insert(
+            "Keys".to_string(),
+            AttributeValue::bs(vec![&b"ab"[..], &b"c"[..]]),
+        );
+        image.insert(
+            "Nested".to_string(),
+            AttributeValue::l(vec![AttributeValue::n(1), AttributeValue::null()]),
+        );
+        let event = DynamoDBEvent::new(vec![DynamoDBEventRecord::builder()
+            .event_id("1")
+            .key("Id", AttributeValue::b(&b"id"[..]))
+            .new_image(image)
+            .build()]);
+
+        let msgpack = rmp_serde::to_vec_named(&event).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<DynamoDBEvent>(&msgpack).unwrap(),
+            event
+        );
+        // Raw bytes, not base64 text.
+        assert!(msgpack.windows(4).any(|w| w == b"\x00png"));
+
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&event, &mut cbor).unwrap();
+        assert_eq!(
+            ciborium::from_reader::<DynamoDBEvent, _>(&cbor[..]).unwrap(),
+            event
+        );
+
+        // Positional struct encoding drops the names of the skipped fields.
+        let positional = rmp_serde::to_vec(&AttributeValue::s("a")).unwrap();
+        assert!(rmp_serde::from_slice::<AttributeValue>(&positional).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Decoding for compressed payloads, such as the base64-encoded gzip data of
+//! CloudWatch Logs subscription events, enabled with the `flate2` feature.
+
+use super::base64::{self, DecodeError};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+
+/// Returned when a compressed payload cannot be decoded.
+#[derive(Debug)]
+pub enum DecompressError {
+    Base64(DecodeError),
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecompressError::Base64(err) => write!(f, "invalid base64: {}", err),
+            DecompressError::Io(err) => write!(f, "invalid compressed data: {}", err),
+            DecompressError::Json(err) => write!(f, "invalid JSON: {}", err),
+        }
+    }
+}
+
+impl Error for DecompressError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DecompressError::Base64(err) => Some(err),
+            DecompressError::Io(err) => Some(err),
+            DecompressError::Json(err) => Some(err),
+        }
+    }
+}
+
+fn read_all(mut reader: impl Read) -> Result<Vec<u8>, DecompressError> {
+    let mut output = Vec::new();
+    reader
+        .read_to_end(&mut output)
+        .map_err(DecompressError::Io)?;
+    Ok(output)
+}
+
+pub fn decode_gzip(input: &[u8]) -> Result<Vec<u8>, DecompressError> {
+    read_all(GzDecoder::new(input))
+}
+
+pub fn decode_zlib(input: &[u8]) -> Result<Vec<u8>, DecompressError> {
+    read_all(ZlibDecoder::new(input))
+}
+
+pub fn decode_base64_gzip(input: &str) -> Result<Vec<u8>, DecompressError> {
+    decode_gzip(&base64::decode(input).map_err(DecompressError::Base64)?)
+}
+
+pub fn decode_base64_zlib(input: &str) -> Result<Vec<u8>, DecompressError> {
+    decode_zlib(&base64::decode(input).map_err(DecompressError::Base64)?)
+}
+
+/// Decodes base64-encoded gzip data and parses the result as JSON.
+pub fn decode_base64_gzip_json<T: DeserializeOwned>(input: &str) -> Result<T, DecompressError> {
+    serde_json::from_slice(&decode_base64_gzip(input)?).map_err(DecompressError::Json)
+}
+
+/// A JSON value that arrives as a base64-encoded gzip string, e.g. the
+/// `awslogs.data` field of a CloudWatch Logs event. Deserialize-only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GzipJson<T>(pub T);
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for GzipJson<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<GzipJson<T>, D::Error> {
+        let data = String::deserialize(deserializer)?;
+        decode_base64_gzip_json(&data)
+            .map(GzipJson)
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+    use serde_json::{json, Value};
+    use std::io::Write;
+
+    #[test]
+    fn decode_payloads() {
+        let data = br#"{"logGroup":"/aws/lambda/f","logEvents":[]}"#;
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(data).unwrap();
+        let gzip = base64::encode(&gzip.finish().unwrap());
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(data).unwrap();
+        let zlib = base64::encode(&zlib.finish().unwrap());
+
+        assert_eq!(decode_base64_gzip(&gzip).unwrap(), data);
+        assert_eq!(decode_base64_zlib(&zlib).unwrap(), data);
+
+        #[derive(Deserialize)]
+        struct AwsLogs {
+            data: GzipJson<Value>,
+        }
+        let logs: AwsLogs = serde_json::from_value(json!({ "data": gzip })).unwrap();
+        assert_eq!(logs.data.0["logGroup"], "/aws/lambda/f");
+
+        assert!(matches!(
+            decode_base64_gzip("!"),
+            Err(DecompressError::Base64(_))
+        ));
+        assert!(matches!(
+            decode_base64_gzip(&zlib),
+            Err(DecompressError::Io(_))
+        ));
+        let mut truncated = GzEncoder::new(Vec::new(), Compression::default());
+        truncated.write_all(b"{").unwrap();
+        let truncated = base64::encode(&truncated.finish().unwrap());
+        assert!(matches!(
+            decode_base64_gzip_json::<Value>(&truncated),
+            Err(DecompressError::Json(_))
+        ));
+    }
+}
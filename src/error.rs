@@ -0,0 +1,369 @@
+//! Parse errors that say where in an event parsing failed, for the
+//! `from_slice` and `from_value` constructors on event types and the
+//! stricter, validating and streaming variants of them.
+
+#[cfg(feature = "dynamodb")]
+use crate::dynamodb::ValidationError;
+use serde::de::{Deserialize, DeserializeOwned};
+use serde_json::error::Category;
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+
+// The most input quoted in an error's snippet, in bytes.
+const SNIPPET_LEN: usize = 40;
+
+/// Returned when an event cannot be parsed. `module` names the event type's
+/// module, e.g. `dynamodb`. Non-exhaustive, since the variants depend on
+/// which features are enabled.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EventParseError {
+    /// The input is not valid JSON.
+    Syntax {
+        module: &'static str,
+        line: usize,
+        column: usize,
+        snippet: String,
+        source: serde_json::Error,
+    },
+    /// The input is valid JSON but does not match the event's shape. `path`
+    /// locates the offending field, e.g. `Records[0].eventName`.
+    Data {
+        module: &'static str,
+        path: String,
+        snippet: String,
+        source: serde_json::Error,
+    },
+    /// The event parsed, but an attribute value is malformed; see
+    /// `DynamoDBEvent::from_slice_validated`.
+    #[cfg(feature = "dynamodb")]
+    Invalid {
+        module: &'static str,
+        source: ValidationError,
+    },
+    /// The event has fields the types do not model, at `paths`; see
+    /// `DynamoDBEvent::from_slice_strict`.
+    UnknownFields {
+        module: &'static str,
+        paths: Vec<String>,
+    },
+}
+
+impl EventParseError {
+    pub fn module(&self) -> &'static str {
+        match self {
+            EventParseError::Syntax { module, .. }
+            | EventParseError::Data { module, .. }
+            | EventParseError::UnknownFields { module, .. } => module,
+            #[cfg(feature = "dynamodb")]
+            EventParseError::Invalid { module, .. } => module,
+        }
+    }
+
+    /// The input around the failure, truncated. Empty for errors not tied to
+    /// one place in the input.
+    pub fn snippet(&self) -> &str {
+        match self {
+            EventParseError::Syntax { snippet, .. } | EventParseError::Data { snippet, .. } => {
+                snippet
+            }
+            _ => "",
+        }
+    }
+
+    // For errors found without the whole input at hand, such as while
+    // streaming records, which can only be located by `path`.
+    #[cfg_attr(not(feature = "dynamodb"), allow(dead_code))]
+    pub(crate) fn at_path(
+        module: &'static str,
+        path: String,
+        source: serde_json::Error,
+    ) -> EventParseError {
+        match source.classify() {
+            Category::Data => EventParseError::Data {
+                module,
+                path,
+                snippet: String::new(),
+                source,
+            },
+            Category::Syntax | Category::Eof | Category::Io => EventParseError::Syntax {
+                module,
+                line: source.line(),
+                column: source.column(),
+                snippet: String::new(),
+                source,
+            },
+        }
+    }
+
+    // Locates `source`, an error from parsing `input`.
+    pub(crate) fn new(
+        module: &'static str,
+        input: &[u8],
+        source: serde_json::Error,
+    ) -> EventParseError {
+        let offset = byte_offset(input, source.line(), source.column());
+        let snippet = snippet(input, offset);
+        match source.classify() {
+            Category::Data => EventParseError::Data {
+                module,
+                path: path_at(&input[..offset]),
+                snippet,
+                source,
+            },
+            Category::Syntax | Category::Eof | Category::Io => EventParseError::Syntax {
+                module,
+                line: source.line(),
+                column: source.column(),
+                snippet,
+                source,
+            },
+        }
+    }
+}
+
+// Errors located only by path, such as those from the streaming reader, have
+// no snippet to show.
+fn write_snippet(f: &mut fmt::Formatter<'_>, snippet: &str) -> fmt::Result {
+    if snippet.is_empty() {
+        Ok(())
+    } else {
+        write!(f, " near `{}`", snippet)
+    }
+}
+
+impl fmt::Display for EventParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventParseError::Syntax {
+                module,
+                snippet,
+                source,
+                ..
+            } => {
+                write!(f, "invalid {} event JSON", module)?;
+                write_snippet(f, snippet)?;
+                write!(f, ": {}", source)
+            }
+            EventParseError::Data {
+                module,
+                path,
+                snippet,
+                source,
+            } => {
+                let path = if path.is_empty() { "top level" } else { path };
+                write!(f, "invalid {} event at {}", module, path)?;
+                write_snippet(f, snippet)?;
+                write!(f, ": {}", source)
+            }
+            #[cfg(feature = "dynamodb")]
+            EventParseError::Invalid { module, source } => {
+                write!(f, "invalid {} event: {}", module, source)
+            }
+            EventParseError::UnknownFields { module, paths } => write!(
+                f,
+                "unknown fields in {} event: {}",
+                module,
+                paths.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for EventParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EventParseError::Syntax { source, .. } | EventParseError::Data { source, .. } => {
+                Some(source)
+            }
+            #[cfg(feature = "dynamodb")]
+            EventParseError::Invalid { source, .. } => Some(source),
+            EventParseError::UnknownFields { .. } => None,
+        }
+    }
+}
+
+pub(crate) fn from_slice<'a, T: Deserialize<'a>>(
+    module: &'static str,
+    input: &'a [u8],
+) -> Result<T, EventParseError> {
+    serde_json::from_slice(input).map_err(|err| EventParseError::new(module, input, err))
+}
+
+// Like `from_slice`, with simd-json when the `simd` feature is on. It needs a
+// mutable copy of the input and reports no path, so a failure is parsed again
+// with serde_json to locate it.
+#[cfg(feature = "simd")]
+pub(crate) fn from_slice_owned<T: DeserializeOwned>(
+    module: &'static str,
+    input: &[u8],
+) -> Result<T, EventParseError> {
+    let mut copy = input.to_vec();
+    simd_json::serde::from_slice(&mut copy).or_else(|_| from_slice(module, input))
+}
+
+#[cfg(not(feature = "simd"))]
+pub(crate) fn from_slice_owned<T: DeserializeOwned>(
+    module: &'static str,
+    input: &[u8],
+) -> Result<T, EventParseError> {
+    from_slice(module, input)
+}
+
+/// Values carry no positions, so only a failure goes through the JSON text,
+/// to locate it like `from_slice` does.
+#[cfg_attr(not(feature = "dynamodb"), allow(dead_code))]
+pub(crate) fn from_value<T: DeserializeOwned>(
+    module: &'static str,
+    value: &Value,
+) -> Result<T, EventParseError> {
+    T::deserialize(value).or_else(|_| {
+        let input =
+            serde_json::to_vec(value).map_err(|err| EventParseError::new(module, &[], err))?;
+        from_slice(module, &input)
+    })
+}
+
+// serde_json reports 1-based lines and the column of the last byte read.
+fn byte_offset(input: &[u8], line: usize, column: usize) -> usize {
+    let line_start: usize = input
+        .split(|&b| b == b'\n')
+        .take(line.saturating_sub(1))
+        .map(|line| line.len() + 1)
+        .sum();
+    (line_start + column).min(input.len())
+}
+
+fn snippet(input: &[u8], offset: usize) -> String {
+    let start = offset.saturating_sub(SNIPPET_LEN * 3 / 4);
+    let end = (start + SNIPPET_LEN).min(input.len());
+    String::from_utf8_lossy(&input[start..end])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+enum Frame {
+    Object { key: Option<String> },
+    Array { index: usize },
+}
+
+// The path of the value being parsed once `input` has been read, found by
+// tracking the open objects and arrays and the current key of each.
+fn path_at(input: &[u8]) -> String {
+    let mut stack = Vec::new();
+    let mut expect_key = false;
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'{' => {
+                stack.push(Frame::Object { key: None });
+                expect_key = true;
+            }
+            b'[' => stack.push(Frame::Array { index: 0 }),
+            // Errors for a whole object, such as a missing field, are reported
+            // at its closing brace, where the parent's key is its path.
+            b'}' | b']' => {
+                stack.pop();
+            }
+            b',' => match stack.last_mut() {
+                Some(Frame::Array { index }) => *index += 1,
+                Some(Frame::Object { .. }) => expect_key = true,
+                None => {}
+            },
+            b'"' => {
+                let start = i + 1;
+                i += 1;
+                while i < input.len() && input[i] != b'"' {
+                    if input[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                if expect_key {
+                    if let Some(Frame::Object { key }) = stack.last_mut() {
+                        let end = i.min(input.len());
+                        *key = Some(String::from_utf8_lossy(&input[start..end]).into_owned());
+                    }
+                    expect_key = false;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut path = String::new();
+    for frame in stack {
+        match frame {
+            Frame::Object { key: Some(key) } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(&key);
+            }
+            Frame::Object { key: None } => {}
+            Frame::Array { index } => path.push_str(&format!("[{}]", index)),
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paths() {
+        assert_eq!(path_at(br#"{"a": [1, {"b": "x""#), "a[1].b");
+        assert_eq!(path_at(br#"{"a": {"b": 1}, "c": tru"#), "c");
+        assert_eq!(path_at(br#"{"a\"b": ["#), r#"a\"b[0]"#);
+        assert_eq!(path_at(br#"{"a": {"b": 1}"#), "a");
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn owned_parsing() {
+        use crate::dynamodb::DynamoDBEvent;
+        use crate::fixtures::dynamodb::{INSERT_EVENT_JSON, MODIFY_EVENT_JSON, REMOVE_EVENT_JSON};
+
+        for fixture in &[INSERT_EVENT_JSON, MODIFY_EVENT_JSON, REMOVE_EVENT_JSON] {
+            let event: DynamoDBEvent = from_slice_owned("dynamodb", fixture).unwrap();
+            assert_eq!(event, serde_json::from_slice(fixture).unwrap());
+        }
+        let err = from_slice_owned::<DynamoDBEvent>("dynamodb", br#"{"Records": [1]}"#);
+        assert!(matches!(err, Err(EventParseError::Data { path, .. }) if path == "Records[0]"));
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn located_errors() {
+        use crate::dynamodb::DynamoDBEvent;
+
+        let input = String::from_utf8(crate::fixtures::dynamodb::INSERT_EVENT_JSON.to_vec())
+            .unwrap()
+            .replace(r#""SizeBytes": 112"#, r#""SizeBytes": "large""#);
+        let err = DynamoDBEvent::from_slice(input.as_bytes()).unwrap_err();
+        match &err {
+            EventParseError::Data { module, path, .. } => {
+                assert_eq!(*module, "dynamodb");
+                assert_eq!(path, "Records[0].dynamodb.SizeBytes");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(err.snippet().contains(r#""large""#));
+
+        let value = serde_json::json!({"Records": [{"awsRegion": "r"}]});
+        let err = DynamoDBEvent::from_value(value).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("invalid dynamodb event at Records[0] near"),
+            "{}",
+            err
+        );
+
+        let err = DynamoDBEvent::from_slice(b"{\"Records\": [}").unwrap_err();
+        assert!(matches!(err, EventParseError::Syntax { line: 1, .. }));
+    }
+}
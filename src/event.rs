@@ -0,0 +1,218 @@
+//! A single type for "any event", for Lambdas subscribed to several sources
+//! and middleware that handles events generically.
+
+#[cfg(feature = "dynamodb")]
+use crate::dynamodb::DynamoDBEvent;
+use crate::error::{self, EventParseError};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// An incoming event, identified from its content. Payloads from sources this
+/// crate has no types for, or whose feature is disabled, are kept as `Unknown`.
+/// Non-exhaustive, since the variants depend on which features are enabled.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LambdaEvent {
+    #[cfg(feature = "dynamodb")]
+    DynamoDB(DynamoDBEvent),
+    Unknown(Value),
+}
+
+impl LambdaEvent {
+    /// The `eventSource` of the payload's first record, e.g. `aws:dynamodb`.
+    fn event_source(value: &Value) -> Option<&str> {
+        value.get("Records")?.get(0)?.get("eventSource")?.as_str()
+    }
+
+    fn detect(value: Value) -> Result<LambdaEvent, serde_json::Error> {
+        match LambdaEvent::event_source(&value) {
+            #[cfg(feature = "dynamodb")]
+            Some("aws:dynamodb") => serde_json::from_value(value).map(LambdaEvent::DynamoDB),
+            _ => Ok(LambdaEvent::Unknown(value)),
+        }
+    }
+
+    /// Identifies and parses `value`. A payload that looks like a known
+    /// source but does not parse as one is an error, not `Unknown`.
+    pub fn from_value(value: Value) -> Result<LambdaEvent, EventParseError> {
+        match LambdaEvent::event_source(&value) {
+            #[cfg(feature = "dynamodb")]
+            Some("aws:dynamodb") => DynamoDBEvent::from_value(value).map(LambdaEvent::DynamoDB),
+            _ => Ok(LambdaEvent::Unknown(value)),
+        }
+    }
+
+    /// Like `from_value`, parsing `input` once. Errors say where in `input`
+    /// parsing failed.
+    pub fn from_slice(input: &[u8]) -> Result<LambdaEvent, EventParseError> {
+        let value: Value = error::from_slice_owned("event", input)?;
+        match LambdaEvent::event_source(&value) {
+            #[cfg(feature = "dynamodb")]
+            Some("aws:dynamodb") => match serde_json::from_value(value) {
+                Ok(event) => Ok(LambdaEvent::DynamoDB(event)),
+                // Values carry no positions, so only a failure is parsed again,
+                // to locate it.
+                Err(_) => DynamoDBEvent::from_slice(input).map(LambdaEvent::DynamoDB),
+            },
+            _ => Ok(LambdaEvent::Unknown(value)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LambdaEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<LambdaEvent, D::Error> {
+        LambdaEvent::detect(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for LambdaEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.serialize(serializer),
+            LambdaEvent::Unknown(value) => value.serialize(serializer),
+        }
+    }
+}
+
+/// Metadata every event source provides, for logging and metrics middleware
+/// that handles events of any type.
+pub trait LambdaEventSource {
+    /// The ARN of the stream, queue or topic the event came from. Records in
+    /// one invocation always share a source, so this is the first record's.
+    fn source_arn(&self) -> Option<&str>;
+
+    /// The region of the event source.
+    fn aws_region(&self) -> Option<&str>;
+
+    /// The unique ID of each record, in order.
+    fn event_ids(&self) -> Vec<&str>;
+
+    fn record_count(&self) -> usize;
+}
+
+#[cfg(feature = "dynamodb")]
+impl LambdaEventSource for DynamoDBEvent {
+    fn source_arn(&self) -> Option<&str> {
+        Some(self.records.first()?.event_source_arn.as_str())
+    }
+
+    fn aws_region(&self) -> Option<&str> {
+        Some(self.records.first()?.aws_region.as_str())
+    }
+
+    fn event_ids(&self) -> Vec<&str> {
+        self.records
+            .iter()
+            .map(|record| record.event_id.as_str())
+            .collect()
+    }
+
+    fn record_count(&self) -> usize {
+        self.records.len()
+    }
+}
+
+/// Unknown payloads report only what they share with known sources: the
+/// `eventSourceARN`, `awsRegion` and `eventID` fields of their `Records`.
+impl LambdaEventSource for LambdaEvent {
+    fn source_arn(&self) -> Option<&str> {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.source_arn(),
+            LambdaEvent::Unknown(value) => {
+                unknown_records(value).next()?["eventSourceARN"].as_str()
+            }
+        }
+    }
+
+    fn aws_region(&self) -> Option<&str> {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.aws_region(),
+            LambdaEvent::Unknown(value) => unknown_records(value).next()?["awsRegion"].as_str(),
+        }
+    }
+
+    fn event_ids(&self) -> Vec<&str> {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.event_ids(),
+            LambdaEvent::Unknown(value) => unknown_records(value)
+                .filter_map(|record| record["eventID"].as_str())
+                .collect(),
+        }
+    }
+
+    fn record_count(&self) -> usize {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.record_count(),
+            LambdaEvent::Unknown(value) => unknown_records(value).count(),
+        }
+    }
+}
+
+fn unknown_records(value: &Value) -> impl Iterator<Item = &Value> {
+    value["Records"].as_array().into_iter().flatten()
+}
+
+#[cfg(feature = "dynamodb")]
+impl From<DynamoDBEvent> for LambdaEvent {
+    fn from(event: DynamoDBEvent) -> LambdaEvent {
+        LambdaEvent::DynamoDB(event)
+    }
+}
+
+#[cfg(all(test, feature = "dynamodb"))]
+mod test {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::json;
+
+    #[test]
+    fn detects_sources() {
+        let event: LambdaEvent =
+            serde_json::from_slice(fixtures::dynamodb::INSERT_EVENT_JSON).unwrap();
+        assert_eq!(event, fixtures::dynamodb::insert_event().into());
+
+        let other = json!({"Records": [{"eventSource": "aws:sqs", "body": "hi"}]});
+        let event: LambdaEvent = serde_json::from_value(other.clone()).unwrap();
+        assert_eq!(event, LambdaEvent::Unknown(other.clone()));
+        assert_eq!(serde_json::to_value(&event).unwrap(), other);
+
+        let event: LambdaEvent =
+            serde_json::from_slice(fixtures::dynamodb::INSERT_EVENT_JSON).unwrap();
+        assert_eq!(event.record_count(), 1);
+        assert_eq!(event.event_ids(), vec!["7de3041dd709b024af6f29e4fa13d34c"]);
+        assert_eq!(event.aws_region(), Some("region"));
+        assert!(event.source_arn().unwrap().starts_with("arn:aws:dynamodb:"));
+
+        let unknown = LambdaEvent::Unknown(json!({"Records": [
+            {"eventID": "a", "awsRegion": "eu-west-1"},
+            {"eventID": "b"}
+        ]}));
+        assert_eq!(unknown.event_ids(), vec!["a", "b"]);
+        assert_eq!(unknown.aws_region(), Some("eu-west-1"));
+        assert_eq!(unknown.source_arn(), None);
+        assert_eq!(LambdaEvent::Unknown(json!({})).record_count(), 0);
+
+        let broken = json!({"Records": [{"eventSource": "aws:dynamodb"}]});
+        assert!(serde_json::from_value::<LambdaEvent>(broken.clone()).is_err());
+        assert_eq!(
+            LambdaEvent::from_slice(broken.to_string().as_bytes())
+                .unwrap_err()
+                .module(),
+            "dynamodb"
+        );
+        assert_eq!(
+            LambdaEvent::from_value(broken).unwrap_err().module(),
+            "dynamodb"
+        );
+        assert_eq!(
+            LambdaEvent::from_slice(fixtures::dynamodb::INSERT_EVENT_JSON).unwrap(),
+            fixtures::dynamodb::insert_event().into()
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::dynamodb::DynamoDBEvent;
+
+/// A new item being written to a table with a `NEW_IMAGE` stream.
+pub const INSERT_EVENT_JSON: &[u8] = include_bytes!("example-dynamo-stream-event.json");
+
+/// An existing item being updated on a `NEW_AND_OLD_IMAGES` stream.
+pub const MODIFY_EVENT_JSON: &[u8] = include_bytes!("example-dynamo-stream-modify-event.json");
+
+/// An item being deleted by Time to Live on a `NEW_AND_OLD_IMAGES` stream.
+pub const REMOVE_EVENT_JSON: &[u8] = include_bytes!("example-dynamo-stream-remove-event.json");
+
+/// An insert with only eventID, eventName, Keys, NewImage and SequenceNumber,
+/// as hand-trimmed payloads and some emulators send.
+pub const TRIMMED_EVENT_JSON: &[u8] = include_bytes!("example-dynamo-stream-trimmed-event.json");
+
+pub fn insert_event() -> DynamoDBEvent {
+    serde_json::from_slice(INSERT_EVENT_JSON).expect("insert fixture is valid")
+}
+
+pub fn modify_event() -> DynamoDBEvent {
+    serde_json::from_slice(MODIFY_EVENT_JSON).expect("modify fixture is valid")
+}
+
+pub fn remove_event() -> DynamoDBEvent {
+    serde_json::from_slice(REMOVE_EVENT_JSON).expect("remove fixture is valid")
+}
+
+pub fn trimmed_event() -> DynamoDBEvent {
+    serde_json::from_slice(TRIMMED_EVENT_JSON).expect("trimmed fixture is valid")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dynamodb::DynamoDBOperationType;
+
+    #[test]
+    fn fixtures_parse() {
+        assert_eq!(insert_event().inserts().count(), 1);
+        assert_eq!(modify_event().modifies().count(), 1);
+        let remove = remove_event();
+        assert_eq!(remove.records[0].event_name, DynamoDBOperationType::Remove);
+        assert!(remove.records[0].is_ttl_delete());
+        assert!(modify_event().validate().is_ok());
+
+        let trimmed = trimmed_event();
+        let record = &trimmed.records[0];
+        assert_eq!(record.event_version, "");
+        assert_eq!(record.table_name(), None);
+        assert_eq!(record.dynamodb.size_bytes, 0);
+        assert_eq!(record.dynamodb.stream_view_type, "");
+        assert_eq!(
+            DynamoDBEvent::from_slice_borrowed(TRIMMED_EVENT_JSON)
+                .unwrap()
+                .into_owned(),
+            trimmed
+        );
+        let raw: crate::dynamodb::RawDynamoDBEvent =
+            serde_json::from_slice(TRIMMED_EVENT_JSON).unwrap();
+        assert_eq!(raw.parse().unwrap(), trimmed);
+    }
+}
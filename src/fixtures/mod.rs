@@ -0,0 +1,4 @@
+//! Realistic events for use in tests, enabled with the `fixtures` feature.
+
+#[cfg(feature = "dynamodb")]
+pub mod dynamodb;
@@ -0,0 +1,126 @@
+//! A roundtrip harness for event fixtures, enabled with the `fixtures`
+//! feature so downstream crates can run their own captured events through it.
+
+use crate::canonical;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+
+/// Parses `json` as `T`, serializes it and parses it again, panicking unless:
+///
+/// * both parses produce equal values,
+/// * both serialize to the same bytes in `canonical` form, and
+/// * every key of the input is still present, at the same path, in the
+///   serialized output. Keys whose input value is `null` may be omitted.
+///
+/// The bytes are compared in canonical form, with the keys of every object
+/// sorted, because attribute maps are `HashMap`s and keep no order of their
+/// own; the input's key order is not expected to survive.
+///
+/// Failures name the JSON path that was lost, e.g. `Records[0].dynamodb.Keys`.
+pub fn assert_roundtrip<T>(json: &str) -> T
+where
+    T: DeserializeOwned + Serialize + PartialEq + Debug,
+{
+    let input: Value = serde_json::from_str(json).expect("fixture is not valid JSON");
+    let parsed: T = serde_json::from_str(json).expect("fixture does not parse");
+    let output = canonical::to_vec(&parsed).expect("parsed fixture does not serialize");
+    let reparsed: T = serde_json::from_slice(&output).expect("serialized fixture does not parse");
+    assert_eq!(parsed, reparsed, "value changed across a roundtrip");
+    let reserialized = canonical::to_vec(&reparsed).expect("reparsed fixture does not serialize");
+    assert!(
+        output == reserialized,
+        "bytes changed across a roundtrip:\n{}\n{}",
+        String::from_utf8_lossy(&output),
+        String::from_utf8_lossy(&reserialized)
+    );
+    let output: Value = serde_json::from_slice(&output).expect("canonical JSON is valid");
+    if let Err(path) = keys_preserved(&input, &output, String::new()) {
+        panic!("key {} was lost across a roundtrip", path);
+    }
+    parsed
+}
+
+fn keys_preserved(input: &Value, output: &Value, path: String) -> Result<(), String> {
+    match (input, output) {
+        (Value::Object(input), Value::Object(output)) => {
+            for (key, value) in input {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match output.get(key) {
+                    Some(output) => keys_preserved(value, output, child)?,
+                    None if value.is_null() => {}
+                    None => return Err(child),
+                }
+            }
+            Ok(())
+        }
+        (Value::Array(input), Value::Array(output)) => {
+            for (i, value) in input.iter().enumerate() {
+                let child = format!("{}[{}]", path, i);
+                match output.get(i) {
+                    Some(output) => keys_preserved(value, output, child)?,
+                    None => return Err(child),
+                }
+            }
+            Ok(())
+        }
+        (Value::Object(_), _) | (Value::Array(_), _) => Err(path),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(all(test, feature = "dynamodb"))]
+mod test {
+    use super::*;
+    use crate::dynamodb::DynamoDBEvent;
+    use crate::fixtures::dynamodb::{INSERT_EVENT_JSON, MODIFY_EVENT_JSON, REMOVE_EVENT_JSON};
+    use std::collections::HashMap;
+
+    #[test]
+    fn dynamodb_fixtures_roundtrip() {
+        for data in &[INSERT_EVENT_JSON, MODIFY_EVENT_JSON, REMOVE_EVENT_JSON] {
+            assert_roundtrip::<DynamoDBEvent>(std::str::from_utf8(data).unwrap());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "key b was lost")]
+    fn dropped_keys_fail() {
+        #[derive(Debug, PartialEq, serde::Deserialize, Serialize)]
+        struct OnlyA {
+            a: u8,
+        }
+        assert_roundtrip::<OnlyA>(r#"{"a": 1, "b": 2}"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "bytes changed")]
+    fn unstable_bytes_fail() {
+        // Equal to everything, so only the byte comparison can catch that
+        // each roundtrip adds a character.
+        #[derive(Debug, Serialize)]
+        struct Growing(String);
+        impl PartialEq for Growing {
+            fn eq(&self, _: &Growing) -> bool {
+                true
+            }
+        }
+        impl<'de> serde::Deserialize<'de> for Growing {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(|s| Growing(s + "!"))
+            }
+        }
+        assert_roundtrip::<Growing>(r#""a""#);
+    }
+
+    #[test]
+    fn null_keys_may_be_omitted() {
+        assert_roundtrip::<HashMap<String, u8>>(r#"{"a": 1}"#);
+        assert_roundtrip::<Option<u8>>("null");
+    }
+}
@@ -1 +1,36 @@
+pub mod aws;
+pub mod batch;
+pub mod canonical;
+pub mod context;
+#[cfg(feature = "dynamodb")]
+pub mod dedup;
+#[cfg(feature = "dynamodb")]
 pub mod dynamodb;
+pub mod emf;
+pub mod encoding;
+pub mod error;
+pub mod event;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod golden;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod payload;
+mod platform;
+pub mod record;
+pub mod recorder;
+#[cfg(feature = "dynamodb")]
+pub mod redact;
+#[cfg(feature = "lambda-runtime")]
+pub mod runtime;
+pub mod s3;
+#[cfg(feature = "tracing")]
+pub mod span;
+pub mod summary;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod testing;
+#[cfg(feature = "chrono")]
+pub mod time;
+pub mod trace;
+pub mod warmup;
@@ -0,0 +1,158 @@
+//! OpenTelemetry semantic convention attributes for events, enabled with the
+//! `otel` feature. Attributes are plain key-value pairs, so they can be set
+//! on spans from any version of the OpenTelemetry SDK:
+//!
+//! `span.set_attributes(event.otel_attributes().into_iter().map(|(k, v)| KeyValue::new(k, v)))`
+
+use crate::event::{LambdaEvent, LambdaEventSource};
+use serde_json::Value;
+
+#[cfg(feature = "dynamodb")]
+use crate::dynamodb::DynamoDBEvent;
+
+pub const FAAS_TRIGGER: &str = "faas.trigger";
+pub const CLOUD_PROVIDER: &str = "cloud.provider";
+pub const CLOUD_REGION: &str = "cloud.region";
+pub const FAAS_DOCUMENT_COLLECTION: &str = "faas.document.collection";
+pub const MESSAGING_SYSTEM: &str = "messaging.system";
+pub const MESSAGING_DESTINATION_NAME: &str = "messaging.destination.name";
+pub const MESSAGING_BATCH_MESSAGE_COUNT: &str = "messaging.batch.message_count";
+
+/// Maps an event to the attributes the FaaS and messaging semantic
+/// conventions define for the invocation that received it.
+pub trait OtelAttributes {
+    fn otel_attributes(&self) -> Vec<(&'static str, String)>;
+}
+
+fn common<E: LambdaEventSource>(trigger: &str, event: &E) -> Vec<(&'static str, String)> {
+    let mut attributes = vec![
+        (FAAS_TRIGGER, trigger.to_string()),
+        (CLOUD_PROVIDER, "aws".to_string()),
+    ];
+    if let Some(region) = event.aws_region() {
+        attributes.push((CLOUD_REGION, region.to_string()));
+    }
+    attributes
+}
+
+/// A stream is a `datasource` trigger whose collection is the table.
+#[cfg(feature = "dynamodb")]
+impl OtelAttributes for DynamoDBEvent {
+    fn otel_attributes(&self) -> Vec<(&'static str, String)> {
+        let mut attributes = common("datasource", self);
+        if let Some(table) = self.records.first().and_then(|record| record.table_name()) {
+            attributes.push((FAAS_DOCUMENT_COLLECTION, table.to_string()));
+        }
+        attributes
+    }
+}
+
+/// Unknown SQS and SNS payloads are recognized as `pubsub` triggers, with the
+/// queue or topic name as the destination. Anything else is `other`.
+impl OtelAttributes for LambdaEvent {
+    fn otel_attributes(&self) -> Vec<(&'static str, String)> {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.otel_attributes(),
+            LambdaEvent::Unknown(value) => unknown_attributes(self, value),
+        }
+    }
+}
+
+fn unknown_attributes(event: &LambdaEvent, value: &Value) -> Vec<(&'static str, String)> {
+    match messaging(&value["Records"][0]) {
+        Some((system, destination)) => {
+            let mut attributes = common("pubsub", event);
+            attributes.push((MESSAGING_SYSTEM, system.to_string()));
+            if let Some(destination) = destination {
+                attributes.push((MESSAGING_DESTINATION_NAME, destination.to_string()));
+            }
+            attributes.push((
+                MESSAGING_BATCH_MESSAGE_COUNT,
+                event.record_count().to_string(),
+            ));
+            attributes
+        }
+        None => common("other", event),
+    }
+}
+
+// The messaging system of a record and the name of its queue or topic, taken
+// from the ARN: `arn:aws:sqs:{region}:{account}:{queue}` for SQS and
+// `arn:aws:sns:{region}:{account}:{topic}:{subscription}` for SNS.
+fn messaging(record: &Value) -> Option<(&'static str, Option<&str>)> {
+    if record["eventSource"] == "aws:sqs" {
+        let queue = record["eventSourceARN"]
+            .as_str()
+            .and_then(|arn| arn.split(':').nth(5));
+        return Some(("aws_sqs", queue));
+    }
+    if record["EventSource"] == "aws:sns" {
+        let topic = record["EventSubscriptionArn"]
+            .as_str()
+            .and_then(|arn| arn.split(':').nth(5));
+        return Some(("aws_sns", topic));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn messaging_attributes() {
+        let sqs = LambdaEvent::Unknown(json!({"Records": [{
+            "eventSource": "aws:sqs",
+            "eventSourceARN": "arn:aws:sqs:eu-west-1:123456789012:orders",
+            "awsRegion": "eu-west-1"
+        }]}));
+        assert_eq!(
+            sqs.otel_attributes(),
+            vec![
+                (FAAS_TRIGGER, "pubsub".to_string()),
+                (CLOUD_PROVIDER, "aws".to_string()),
+                (CLOUD_REGION, "eu-west-1".to_string()),
+                (MESSAGING_SYSTEM, "aws_sqs".to_string()),
+                (MESSAGING_DESTINATION_NAME, "orders".to_string()),
+                (MESSAGING_BATCH_MESSAGE_COUNT, "1".to_string()),
+            ]
+        );
+
+        let sns = LambdaEvent::Unknown(json!({"Records": [{
+            "EventSource": "aws:sns",
+            "EventSubscriptionArn": "arn:aws:sns:us-east-1:123456789012:alerts:2bcfbf39"
+        }]}));
+        let attributes = sns.otel_attributes();
+        assert!(attributes.contains(&(MESSAGING_SYSTEM, "aws_sns".to_string())));
+        assert!(attributes.contains(&(MESSAGING_DESTINATION_NAME, "alerts".to_string())));
+
+        assert_eq!(
+            LambdaEvent::Unknown(json!({})).otel_attributes(),
+            vec![
+                (FAAS_TRIGGER, "other".to_string()),
+                (CLOUD_PROVIDER, "aws".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn datasource_attributes() {
+        let event = crate::testing::dynamodb()
+            .inserts(1)
+            .table("users")
+            .region("eu-west-1")
+            .build();
+        assert_eq!(
+            LambdaEvent::from(event).otel_attributes(),
+            vec![
+                (FAAS_TRIGGER, "datasource".to_string()),
+                (CLOUD_PROVIDER, "aws".to_string()),
+                (CLOUD_REGION, "eu-west-1".to_string()),
+                (FAAS_DOCUMENT_COLLECTION, "users".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Payload size checks against Lambda's invocation limits, and splitting of
+//! record batches for forwarding to size-limited targets.
+
+use serde::Serialize;
+
+/// The largest request or response payload of a synchronous invocation.
+pub const SYNC_LIMIT: usize = 6 * 1024 * 1024;
+
+/// The largest event payload of an asynchronous invocation.
+pub const ASYNC_LIMIT: usize = 256 * 1024;
+
+/// The serialized size of a payload, and how it compares to the limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadSize {
+    pub bytes: usize,
+}
+
+impl PayloadSize {
+    pub fn fits_sync(&self) -> bool {
+        self.bytes <= SYNC_LIMIT
+    }
+
+    pub fn fits_async(&self) -> bool {
+        self.bytes <= ASYNC_LIMIT
+    }
+}
+
+/// Serializes `payload` as JSON to measure it against the invocation limits.
+pub fn check_limits<T: Serialize + ?Sized>(payload: &T) -> Result<PayloadSize, serde_json::Error> {
+    Ok(PayloadSize {
+        bytes: serde_json::to_vec(payload)?.len(),
+    })
+}
+
+/// Splits `records` into chunks whose JSON array is at most `max_bytes`,
+/// preserving order. A record larger than `max_bytes` on its own is still
+/// emitted, alone in its own chunk.
+pub fn split_records<R: Serialize>(
+    records: Vec<R>,
+    max_bytes: usize,
+) -> Result<Vec<Vec<R>>, serde_json::Error> {
+    // The brackets around the array.
+    const OVERHEAD: usize = 2;
+
+    let mut chunks = Vec::new();
+    let mut current: Vec<R> = Vec::new();
+    let mut current_size = OVERHEAD;
+    for record in records {
+        let size = serde_json::to_vec(&record)?.len();
+        let separator = if current.is_empty() { 0 } else { 1 };
+        if !current.is_empty() && current_size + separator + size > max_bytes {
+            chunks.push(std::mem::take(&mut current));
+            current_size = OVERHEAD;
+        }
+        current_size += if current.is_empty() { size } else { size + 1 };
+        current.push(record);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn limits() {
+        let size = check_limits(&json!({ "data": "x".repeat(ASYNC_LIMIT) })).unwrap();
+        assert_eq!(size.bytes, ASYNC_LIMIT + r#"{"data":""}"#.len());
+        assert!(size.fits_sync());
+        assert!(!size.fits_async());
+
+        let records = vec!["aaaa", "bbbb", "cccc", "dddddddddddd"];
+        let chunks = split_records(records.clone(), r#"["aaaa","bbbb"]"#.len()).unwrap();
+        assert_eq!(
+            chunks,
+            vec![vec!["aaaa", "bbbb"], vec!["cccc"], vec!["dddddddddddd"]]
+        );
+        for chunk in &chunks[..2] {
+            assert!(check_limits(chunk).unwrap().bytes <= 15);
+        }
+        assert_eq!(split_records(records, usize::MAX).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,25 @@
+//! Differences between targets, kept in one place so the rest of the crate
+//! builds unchanged on all of them. `wasm32-unknown-unknown` has no clock:
+//! `SystemTime::now` panics there, so the time is unknown instead.
+
+use std::time::SystemTime;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn now() -> Option<SystemTime> {
+    Some(SystemTime::now())
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn now() -> Option<SystemTime> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn host_clock() {
+        assert!(now().is_some());
+    }
+}
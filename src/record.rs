@@ -0,0 +1,130 @@
+//! A source-independent view of records, for pipelines that handle several
+//! triggers in one processing loop.
+
+use crate::event::LambdaEvent;
+use serde_json::Value;
+use std::time::SystemTime;
+
+#[cfg(feature = "dynamodb")]
+use crate::dynamodb::{DynamoDBEvent, DynamoDBEventRecord};
+
+/// One record of any source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnyRecord {
+    /// The record's unique ID, e.g. a stream record's `eventID`.
+    pub id: String,
+    /// The `eventSource`, e.g. `aws:dynamodb`.
+    pub source: String,
+    /// When the record was written, for sources that say.
+    pub timestamp: Option<SystemTime>,
+    /// The source-specific content: the stream record of a DynamoDB record,
+    /// the whole record for unknown sources.
+    pub payload: Value,
+}
+
+impl AnyRecord {
+    /// The size of the payload as JSON.
+    pub fn payload_bytes(&self) -> usize {
+        self.payload.to_string().len()
+    }
+}
+
+#[cfg(feature = "dynamodb")]
+impl From<DynamoDBEventRecord> for AnyRecord {
+    fn from(record: DynamoDBEventRecord) -> AnyRecord {
+        let timestamp = record.dynamodb.created_at();
+        let source = if record.event_source.is_empty() {
+            "aws:dynamodb".to_string()
+        } else {
+            record.event_source
+        };
+        AnyRecord {
+            id: record.event_id,
+            source,
+            timestamp,
+            payload: serde_json::to_value(record.dynamodb)
+                .expect("stream records always serialize"),
+        }
+    }
+}
+
+#[cfg(feature = "dynamodb")]
+impl From<DynamoDBEvent> for Vec<AnyRecord> {
+    fn from(event: DynamoDBEvent) -> Vec<AnyRecord> {
+        event.records.into_iter().map(AnyRecord::from).collect()
+    }
+}
+
+impl LambdaEvent {
+    /// The event's records, in order. Records of unknown sources take their
+    /// ID from `eventID` or `messageId` and their source from `eventSource`
+    /// or `EventSource`, and have no timestamp.
+    pub fn into_records(self) -> Vec<AnyRecord> {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.into(),
+            LambdaEvent::Unknown(mut value) => match value["Records"].take() {
+                Value::Array(records) => records.into_iter().map(unknown_record).collect(),
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+fn unknown_record(record: Value) -> AnyRecord {
+    let field = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| record[*name].as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    AnyRecord {
+        id: field(&["eventID", "messageId"]),
+        source: field(&["eventSource", "EventSource"]),
+        timestamp: None,
+        payload: record,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    #[cfg(feature = "dynamodb")]
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn unknown_records() {
+        let event = LambdaEvent::Unknown(json!({"Records": [
+            {"messageId": "m-1", "eventSource": "aws:sqs", "body": "hi"},
+            {"EventSource": "aws:sns"}
+        ]}));
+        let records = event.into_records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].id, "m-1");
+        assert_eq!(records[0].source, "aws:sqs");
+        assert_eq!(records[0].payload["body"], "hi");
+        assert_eq!(records[1].id, "");
+        assert_eq!(records[1].source, "aws:sns");
+        assert!(LambdaEvent::Unknown(json!({})).into_records().is_empty());
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn dynamodb_records() {
+        let mut event = crate::fixtures::dynamodb::insert_event();
+        event.records[0].dynamodb.approximate_creation_date_time = Some(1.5);
+        let stream = serde_json::to_value(&event.records[0].dynamodb).unwrap();
+        let records = LambdaEvent::from(event).into_records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, "7de3041dd709b024af6f29e4fa13d34c");
+        assert_eq!(records[0].source, "aws:dynamodb");
+        assert_eq!(
+            records[0].timestamp,
+            Some(UNIX_EPOCH + Duration::from_millis(1500))
+        );
+        assert_eq!(records[0].payload_bytes(), stream.to_string().len());
+        assert_eq!(records[0].payload, stream);
+    }
+}
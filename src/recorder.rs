@@ -0,0 +1,179 @@
+//! Recording of received events to a JSONL sink, one event per line, and
+//! replay of recordings through a handler for regression tests.
+//!
+//! Events are written in canonical form (see `canonical`), so recording the
+//! same event twice gives identical lines and recordings diff cleanly.
+
+use crate::canonical;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+type Hook = Box<dyn Fn(&mut Value) + Send + Sync>;
+
+/// Writes events to `sink` as JSON lines, passing each through the redaction
+/// hooks first, in the order they were added. For DynamoDB events, a
+/// `redact::Redactor` can also be applied before recording.
+pub struct Recorder<W> {
+    sink: W,
+    hooks: Vec<Hook>,
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(sink: W) -> Self {
+        Recorder {
+            sink,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Adds a hook that edits each event's JSON before it's written, e.g. to
+    /// blank out a field that holds personal data.
+    pub fn redact_with<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut Value) + Send + Sync + 'static,
+    {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    pub fn record<T: Serialize + ?Sized>(&mut self, event: &T) -> io::Result<()> {
+        let mut value = serde_json::to_value(event)?;
+        for hook in &self.hooks {
+            hook(&mut value);
+        }
+        let mut line = canonical::to_vec(&value)?;
+        line.push(b'\n');
+        self.sink.write_all(&line)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+}
+
+impl<W> fmt::Debug for Recorder<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recorder")
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+/// Returned when a recording cannot be read back.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The event on `line`, counting from 1, does not parse as the handler's
+    /// event type.
+    Event {
+        line: usize,
+        source: serde_json::Error,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "failed to read recording: {}", err),
+            ReplayError::Event { line, source } => {
+                write!(f, "invalid event on line {} of recording: {}", line, source)
+            }
+        }
+    }
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplayError::Io(err) => Some(err),
+            ReplayError::Event { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(err: io::Error) -> ReplayError {
+        ReplayError::Io(err)
+    }
+}
+
+/// Parses every event in a recording, skipping blank lines.
+pub fn read<T: DeserializeOwned, R: BufRead>(recording: R) -> Result<Vec<T>, ReplayError> {
+    let mut events = Vec::new();
+    for (i, line) in recording.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line).map_err(|source| ReplayError::Event {
+            line: i + 1,
+            source,
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Runs `handler` over every event in a recording, in order, and returns its
+/// outputs for comparison against a known-good run. Nothing is run unless the
+/// whole recording parses.
+pub fn replay<T, R, O, F>(recording: R, mut handler: F) -> Result<Vec<O>, ReplayError>
+where
+    T: DeserializeOwned,
+    R: BufRead,
+    F: FnMut(T) -> O,
+{
+    Ok(read(recording)?.into_iter().map(&mut handler).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn record_and_replay() {
+        let mut recorder = Recorder::new(Vec::new()).redact_with(|event| {
+            if let Some(user) = event.get_mut("user") {
+                *user = json!("[REDACTED]");
+            }
+        });
+        recorder.record(&json!({"user": "ann", "n": 1})).unwrap();
+        recorder.record(&json!({"n": 2, "extra": true})).unwrap();
+        let recording = recorder.into_inner();
+        assert_eq!(
+            String::from_utf8(recording.clone()).unwrap(),
+            "{\"n\":1,\"user\":\"[REDACTED]\"}\n{\"extra\":true,\"n\":2}\n"
+        );
+
+        let outputs = replay(&recording[..], |event: Value| event["n"].as_u64()).unwrap();
+        assert_eq!(outputs, vec![Some(1), Some(2)]);
+
+        let err = read::<Value, _>(&b"{}\n\n{"[..]).unwrap_err();
+        assert!(matches!(err, ReplayError::Event { line: 3, .. }), "{}", err);
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn typed_events() {
+        use crate::dynamodb::DynamoDBEvent;
+        use crate::fixtures::dynamodb;
+
+        let mut recorder = Recorder::new(Vec::new());
+        for event in &[dynamodb::insert_event(), dynamodb::modify_event()] {
+            recorder.record(event).unwrap();
+        }
+        let recording = recorder.into_inner();
+        let events: Vec<DynamoDBEvent> = read(&recording[..]).unwrap();
+        assert_eq!(
+            events,
+            vec![dynamodb::insert_event(), dynamodb::modify_event()]
+        );
+        let counts = replay(&recording[..], |event: DynamoDBEvent| event.records.len());
+        assert_eq!(counts.unwrap(), vec![1, 1]);
+    }
+}
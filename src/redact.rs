@@ -0,0 +1,188 @@
+//! Sanitized copies of events for logging, with sensitive attributes
+//! replaced by a placeholder.
+
+use crate::dynamodb::{
+    AttributePath, AttributeValue, DynamoDBEvent, DynamoDBEventRecord, DynamoDBStreamRecord,
+    PathSegment,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Decides which attributes to redact: any attribute whose name is on the
+/// deny list, at any depth, and any attribute at one of the given paths.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    fields: HashSet<String>,
+    paths: HashSet<AttributePath>,
+    replacement: String,
+}
+
+impl Default for Redactor {
+    fn default() -> Redactor {
+        Redactor {
+            fields: HashSet::new(),
+            paths: HashSet::new(),
+            replacement: "[REDACTED]".to_string(),
+        }
+    }
+}
+
+impl Redactor {
+    pub fn new() -> Redactor {
+        Redactor::default()
+    }
+
+    /// Redacts every attribute named `name`, wherever it appears.
+    pub fn field<S: Into<String>>(mut self, name: S) -> Self {
+        self.fields.insert(name.into());
+        self
+    }
+
+    /// Redacts the attribute at `path`, relative to the item, e.g.
+    /// `user.address[0]`.
+    pub fn path(mut self, path: AttributePath) -> Self {
+        self.paths.insert(path);
+        self
+    }
+
+    /// The string redacted values are replaced with; `[REDACTED]` by default.
+    pub fn replacement<S: Into<String>>(mut self, replacement: S) -> Self {
+        self.replacement = replacement.into();
+        self
+    }
+
+    fn redacts(&self, name: Option<&str>, path: &AttributePath) -> bool {
+        name.is_some_and(|name| self.fields.contains(name)) || self.paths.contains(path)
+    }
+
+    fn redact_item(
+        &self,
+        item: &HashMap<String, AttributeValue>,
+        path: &AttributePath,
+    ) -> HashMap<String, AttributeValue> {
+        item.iter()
+            .map(|(name, value)| {
+                let path = path.child(PathSegment::Attribute(name.clone()));
+                (name.clone(), self.redact_value(Some(name), value, &path))
+            })
+            .collect()
+    }
+
+    fn redact_value(
+        &self,
+        name: Option<&str>,
+        value: &AttributeValue,
+        path: &AttributePath,
+    ) -> AttributeValue {
+        if self.redacts(name, path) {
+            return AttributeValue::s(self.replacement.as_str());
+        }
+        if let Some(m) = &value.m {
+            AttributeValue::m(self.redact_item(m, path))
+        } else if let Some(l) = &value.l {
+            AttributeValue::l(
+                l.iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        let path = path.child(PathSegment::Index(index));
+                        self.redact_value(None, value, &path)
+                    })
+                    .collect(),
+            )
+        } else {
+            value.clone()
+        }
+    }
+}
+
+/// Types that can produce a copy of themselves with sensitive data removed.
+pub trait Redact {
+    fn redact(&self, redactor: &Redactor) -> Self;
+}
+
+impl Redact for HashMap<String, AttributeValue> {
+    fn redact(&self, redactor: &Redactor) -> Self {
+        redactor.redact_item(self, &AttributePath::default())
+    }
+}
+
+/// Keys and both images are redacted, since keys often hold PII such as
+/// email addresses too.
+impl Redact for DynamoDBStreamRecord {
+    fn redact(&self, redactor: &Redactor) -> Self {
+        DynamoDBStreamRecord {
+            keys: self.keys.as_ref().map(|item| item.redact(redactor)),
+            new_image: self.new_image.as_ref().map(|item| item.redact(redactor)),
+            old_image: self.old_image.as_ref().map(|item| item.redact(redactor)),
+            ..self.clone()
+        }
+    }
+}
+
+impl Redact for DynamoDBEventRecord {
+    fn redact(&self, redactor: &Redactor) -> Self {
+        DynamoDBEventRecord {
+            dynamodb: self.dynamodb.redact(redactor),
+            ..self.clone()
+        }
+    }
+}
+
+impl Redact for DynamoDBEvent {
+    fn redact(&self, redactor: &Redactor) -> Self {
+        DynamoDBEvent {
+            records: self
+                .records
+                .iter()
+                .map(|record| record.redact(redactor))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dynamodb::ItemPath;
+    use crate::fixtures;
+    use serde_json::json;
+
+    #[test]
+    fn redacted_copies() {
+        let item: HashMap<String, AttributeValue> = serde_json::from_value(json!({
+            "Email": {"S": "joe@example.com"},
+            "User": {"M": {
+                "Email": {"S": "joe@example.com"},
+                "Cards": {"L": [{"S": "4111"}, {"S": "5500"}]}
+            }},
+            "Id": {"N": "1"}
+        }))
+        .unwrap();
+        let redactor = Redactor::new()
+            .field("Email")
+            .path("User.Cards[1]".parse().unwrap());
+        let redacted = item.redact(&redactor);
+
+        let hidden = Some(&AttributeValue::s("[REDACTED]"));
+        assert_eq!(redacted.get_path("Email"), hidden);
+        assert_eq!(redacted.get_path("User.Email"), hidden);
+        assert_eq!(redacted.get_path("User.Cards[1]"), hidden);
+        assert_eq!(
+            redacted.get_path("User.Cards[0]"),
+            Some(&AttributeValue::s("4111"))
+        );
+        assert_eq!(redacted["Id"], item["Id"]);
+
+        let event = fixtures::dynamodb::modify_event();
+        let redacted = event.redact(&Redactor::new().field("Username").replacement("***"));
+        let stream = &redacted.records[0].dynamodb;
+        assert_eq!(
+            stream.keys.as_ref().unwrap()["Username"],
+            AttributeValue::s("***")
+        );
+        assert_eq!(
+            stream.old_image.as_ref().unwrap()["Username"],
+            AttributeValue::s("***")
+        );
+        assert_eq!(redacted.records[0].event_id, event.records[0].event_id);
+    }
+}
@@ -0,0 +1,93 @@
+//! Adapters from per-record handlers to `lambda_runtime` services, enabled
+//! with the `lambda-runtime` feature:
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), lambda_runtime::Error> {
+//! use aws_lambda_events_extended::runtime::dynamodb_handler;
+//!
+//! lambda_runtime::run(dynamodb_handler(|record, _context| async move {
+//!     println!("{}", record.event_id);
+//!     Ok::<_, std::io::Error>(())
+//! }))
+//! .await
+//! # }
+//! ```
+
+use crate::batch::{self, BatchResponse};
+use crate::dynamodb::{DynamoDBEvent, DynamoDBEventRecord};
+use lambda_runtime::{service_fn, Context, Error, LambdaEvent, Service};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Wraps `handler` in a service that runs it on each record of the event with
+/// `batch::process_owned`, and responds with the failed records. Requires
+/// `ReportBatchItemFailures` on the event source mapping.
+///
+/// The runtime parses the payload, so one that does not parse fails the whole
+/// invocation. Handler errors only mark their record as failed; with the
+/// `tracing` feature each is also logged as an error event.
+pub fn dynamodb_handler<F, Fut, E>(
+    handler: F,
+) -> impl Service<LambdaEvent<DynamoDBEvent>, Response = BatchResponse, Error = Error>
+where
+    F: Fn(DynamoDBEventRecord, Context) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), E>> + Send,
+    E: fmt::Display,
+{
+    let handler = Arc::new(handler);
+    service_fn(move |event: LambdaEvent<DynamoDBEvent>| {
+        let handler = Arc::clone(&handler);
+        async move {
+            let (event, context) = event.into_parts();
+            let outcome =
+                batch::process_owned(event, |record| handler(record, context.clone())).await;
+            #[cfg(feature = "tracing")]
+            for (failure, error) in outcome
+                .response
+                .batch_item_failures
+                .iter()
+                .zip(&outcome.errors)
+            {
+                tracing::error!(
+                    item_identifier = failure.item_identifier.as_str(),
+                    error = %error,
+                    "DynamoDB record failed"
+                );
+            }
+            Ok(outcome.response)
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::batch::test::block_on;
+    use crate::batch::BatchItemFailure;
+    use crate::dynamodb::DynamoDBOperationType;
+    use crate::fixtures;
+
+    #[test]
+    fn dynamodb_batches() {
+        let mut service = dynamodb_handler(|record, _| async move {
+            match record.event_name {
+                DynamoDBOperationType::Remove => Err("no removals"),
+                _ => Ok(()),
+            }
+        });
+
+        let mut event = fixtures::dynamodb::insert_event();
+        event
+            .records
+            .extend(fixtures::dynamodb::remove_event().records);
+        let response =
+            block_on(service.call(LambdaEvent::new(event.clone(), Context::default()))).unwrap();
+        assert_eq!(
+            response.batch_item_failures,
+            vec![BatchItemFailure {
+                item_identifier: event.records[1].dynamodb.sequence_number.clone()
+            }]
+        );
+    }
+}
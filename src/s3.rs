@@ -0,0 +1,225 @@
+//! S3 object locations: `s3://` URIs, the URL-encoded keys of event
+//! notifications, and the prefix and suffix filters notifications use.
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// An object location, e.g. `s3://my-bucket/photos/2024/cat.jpg`. The key is
+/// empty for the bucket itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct S3Uri {
+    pub bucket: String,
+    pub key: String,
+}
+
+impl S3Uri {
+    pub fn new<B: Into<String>, K: Into<String>>(bucket: B, key: K) -> Self {
+        S3Uri {
+            bucket: bucket.into(),
+            key: key.into(),
+        }
+    }
+
+    /// The location of a key as it appears in an event notification, which
+    /// URL-encodes keys.
+    pub fn from_event_key<B: Into<String>>(bucket: B, key: &str) -> Result<Self, KeyDecodeError> {
+        Ok(S3Uri::new(bucket, decode_key(key)?))
+    }
+
+    /// The key's last `/`-separated segment, e.g. `cat.jpg`.
+    pub fn file_name(&self) -> &str {
+        self.key.rsplit('/').next().unwrap_or_default()
+    }
+
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.key.starts_with(prefix)
+    }
+}
+
+impl fmt::Display for S3Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "s3://{}/{}", self.bucket, self.key)
+    }
+}
+
+/// Serialized as the URI string.
+impl Serialize for S3Uri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for S3Uri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<S3Uri, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl FromStr for S3Uri {
+    type Err = S3UriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || S3UriError { uri: s.to_string() };
+        let location = s.strip_prefix("s3://").ok_or_else(error)?;
+        let (bucket, key) = match location.find('/') {
+            Some(end) => (&location[..end], &location[end + 1..]),
+            None => (location, ""),
+        };
+        if bucket.is_empty() {
+            return Err(error());
+        }
+        Ok(S3Uri::new(bucket, key))
+    }
+}
+
+/// Returned when a string is not an `s3://bucket/key` URI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct S3UriError {
+    uri: String,
+}
+
+impl fmt::Display for S3UriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid S3 URI: {:?}", self.uri)
+    }
+}
+
+impl Error for S3UriError {}
+
+/// Decodes an object key from an event notification, where spaces are
+/// encoded as `+` and other special characters as `%XX`, e.g.
+/// `photos/my+cat%281%29.jpg` is `photos/my cat(1).jpg`.
+pub fn decode_key(key: &str) -> Result<String, KeyDecodeError> {
+    let error = || KeyDecodeError {
+        key: key.to_string(),
+    };
+    let bytes = key.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                // from_str_radix alone would also accept a sign, as in "%+1".
+                let hex = key
+                    .get(i + 1..i + 3)
+                    .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .ok_or_else(error)?;
+                decoded.push(u8::from_str_radix(hex, 16).map_err(|_| error())?);
+                i += 2;
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8(decoded).map_err(|_| error())
+}
+
+/// Returned when an event key has a malformed escape or is not UTF-8 once
+/// decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyDecodeError {
+    key: String,
+}
+
+impl fmt::Display for KeyDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid URL-encoded S3 key: {:?}", self.key)
+    }
+}
+
+impl Error for KeyDecodeError {}
+
+/// Matches keys like a notification configuration's filter rules: a key
+/// matches if it starts with the prefix and ends with the suffix, and an
+/// unset rule matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyFilter {
+    prefix: Option<String>,
+    suffix: Option<String>,
+}
+
+impl KeyFilter {
+    pub fn new() -> Self {
+        KeyFilter::default()
+    }
+
+    pub fn prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn suffix<S: Into<String>>(mut self, suffix: S) -> Self {
+        self.suffix = Some(suffix.into());
+        self
+    }
+
+    pub fn matches(&self, key: &str) -> bool {
+        self.prefix
+            .as_ref()
+            .is_none_or(|p| key.starts_with(p.as_str()))
+            && self
+                .suffix
+                .as_ref()
+                .is_none_or(|s| key.ends_with(s.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uris() {
+        let uri: S3Uri = "s3://my-bucket/photos/2024/cat.jpg".parse().unwrap();
+        assert_eq!(uri, S3Uri::new("my-bucket", "photos/2024/cat.jpg"));
+        assert_eq!(uri.file_name(), "cat.jpg");
+        assert!(uri.has_prefix("photos/"));
+        assert_eq!(uri.to_string(), "s3://my-bucket/photos/2024/cat.jpg");
+        assert_eq!(
+            serde_json::to_string(&uri).unwrap(),
+            r#""s3://my-bucket/photos/2024/cat.jpg""#
+        );
+        assert_eq!(
+            serde_json::from_str::<S3Uri>(r#""s3://b/k""#).unwrap(),
+            S3Uri::new("b", "k")
+        );
+
+        assert_eq!("s3://bucket".parse(), Ok(S3Uri::new("bucket", "")));
+        assert_eq!(S3Uri::new("bucket", "").to_string(), "s3://bucket/");
+        for invalid in &["s3:///key", "https://bucket/key", "bucket/key"] {
+            assert!(invalid.parse::<S3Uri>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn event_keys() {
+        assert_eq!(
+            decode_key("photos/my+cat%281%29.jpg").unwrap(),
+            "photos/my cat(1).jpg"
+        );
+        assert_eq!(decode_key("a%2Bb%20c").unwrap(), "a+b c");
+        assert_eq!(decode_key("caf%C3%A9").unwrap(), "café");
+        for invalid in &["100%", "%zz", "%C3", "%é", "%+1", "%-1"] {
+            assert!(decode_key(invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!(
+            S3Uri::from_event_key("b", "a+b").unwrap(),
+            S3Uri::new("b", "a b")
+        );
+    }
+
+    #[test]
+    fn filters() {
+        let filter = KeyFilter::new().prefix("uploads/").suffix(".jpg");
+        assert!(filter.matches("uploads/cat.jpg"));
+        assert!(!filter.matches("uploads/cat.png"));
+        assert!(!filter.matches("other/cat.jpg"));
+        assert!(KeyFilter::new().matches(""));
+    }
+}
@@ -0,0 +1,136 @@
+//! `tracing` spans for events, enabled with the `tracing` feature. Every
+//! source records the same fields, so instrumentation reads alike across
+//! them:
+//!
+//! * `aws.event.source`, the records' `eventSource`, e.g. `aws:dynamodb`
+//! * `aws.region`
+//! * `aws.event.source_arn`
+//! * `aws.event.record_count`
+//! * `aws.event.record_ids`, comma separated
+
+use crate::event::{LambdaEvent, LambdaEventSource};
+use tracing::Span;
+
+#[cfg(feature = "dynamodb")]
+use crate::dynamodb::DynamoDBEvent;
+
+fn event_span<E: LambdaEventSource>(source: &str, event: &E) -> Span {
+    tracing::info_span!(
+        "lambda_event",
+        aws.event.source = source,
+        aws.region = event.aws_region(),
+        aws.event.source_arn = event.source_arn(),
+        aws.event.record_count = event.record_count() as u64,
+        aws.event.record_ids = event.event_ids().join(",").as_str(),
+    )
+}
+
+#[cfg(feature = "dynamodb")]
+impl DynamoDBEvent {
+    /// An info-level span describing the event, to enter while handling it.
+    pub fn span(&self) -> Span {
+        event_span("aws:dynamodb", self)
+    }
+}
+
+impl LambdaEvent {
+    /// An info-level span describing the event, to enter while handling it.
+    /// Unknown events take their source from the first record.
+    pub fn span(&self) -> Span {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.span(),
+            LambdaEvent::Unknown(value) => event_span(
+                value["Records"][0]["eventSource"]
+                    .as_str()
+                    .unwrap_or("unknown"),
+                self,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    type Fields = Arc<Mutex<BTreeMap<String, String>>>;
+
+    // Keeps the fields of every span created, by name.
+    struct FieldCollector(Fields);
+
+    impl Visit for FieldCollector {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl Subscriber for FieldCollector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            span.record(&mut FieldCollector(self.0.clone()));
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    fn span_fields(event: &LambdaEvent) -> BTreeMap<String, String> {
+        let fields = Fields::default();
+        tracing::subscriber::with_default(FieldCollector(fields.clone()), || event.span());
+        let fields = fields.lock().unwrap().clone();
+        fields
+    }
+
+    #[test]
+    fn unknown_spans() {
+        let event = LambdaEvent::Unknown(json!({"Records": [
+            {"eventSource": "aws:sqs", "eventID": "a", "awsRegion": "eu-west-1"},
+            {"eventID": "b"}
+        ]}));
+        let fields = span_fields(&event);
+        assert_eq!(fields["aws.event.source"], "aws:sqs");
+        assert_eq!(fields["aws.region"], "eu-west-1");
+        assert_eq!(fields["aws.event.record_count"], "2");
+        assert_eq!(fields["aws.event.record_ids"], "a,b");
+        assert!(!fields.contains_key("aws.event.source_arn"));
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn dynamodb_spans() {
+        let event = crate::fixtures::dynamodb::insert_event().into();
+        let fields = span_fields(&event);
+        assert_eq!(fields["aws.event.source"], "aws:dynamodb");
+        assert_eq!(fields["aws.region"], "region");
+        assert_eq!(
+            fields["aws.event.record_ids"],
+            "7de3041dd709b024af6f29e4fa13d34c"
+        );
+        assert!(fields["aws.event.source_arn"].starts_with("arn:aws:dynamodb:"));
+    }
+}
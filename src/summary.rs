@@ -0,0 +1,108 @@
+//! Small per-invocation summaries of events, for observability middleware to
+//! emit as metrics without knowing each source's shape.
+
+use crate::event::{LambdaEvent, LambdaEventSource};
+use crate::payload;
+use std::time::Duration;
+
+#[cfg(feature = "dynamodb")]
+use crate::dynamodb::DynamoDBEvent;
+#[cfg(feature = "dynamodb")]
+use crate::platform;
+#[cfg(feature = "dynamodb")]
+use std::time::SystemTime;
+
+/// What an event contained, in metric-sized numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSummary {
+    /// The records' `eventSource`, e.g. `aws:dynamodb`, or `unknown`.
+    pub source: String,
+    pub record_count: usize,
+    /// The size of the event as JSON.
+    pub payload_bytes: usize,
+    /// How long ago the oldest record was written, for sources that
+    /// timestamp their records. `None` on targets without a clock.
+    pub oldest_record_age: Option<Duration>,
+}
+
+#[cfg(feature = "dynamodb")]
+impl DynamoDBEvent {
+    pub fn summary(&self) -> EventSummary {
+        self.summary_at(platform::now())
+    }
+
+    fn summary_at(&self, now: Option<SystemTime>) -> EventSummary {
+        let oldest_record_age = now.and_then(|now| {
+            self.records
+                .iter()
+                .filter_map(|record| record.approximate_age(now))
+                .max()
+        });
+        EventSummary {
+            source: "aws:dynamodb".to_string(),
+            record_count: self.record_count(),
+            payload_bytes: payload_bytes(self),
+            oldest_record_age,
+        }
+    }
+}
+
+impl LambdaEvent {
+    pub fn summary(&self) -> EventSummary {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(event) => event.summary(),
+            LambdaEvent::Unknown(value) => EventSummary {
+                source: value["Records"][0]["eventSource"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_string(),
+                record_count: self.record_count(),
+                payload_bytes: payload_bytes(value),
+                oldest_record_age: None,
+            },
+        }
+    }
+}
+
+fn payload_bytes<T: serde::Serialize>(event: &T) -> usize {
+    payload::check_limits(event)
+        .map(|size| size.bytes)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unknown_summary() {
+        let payload = json!({"Records": [{"eventSource": "aws:sqs"}, {}]});
+        let summary = LambdaEvent::Unknown(payload.clone()).summary();
+        assert_eq!(summary.source, "aws:sqs");
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(summary.payload_bytes, payload.to_string().len());
+        assert_eq!(summary.oldest_record_age, None);
+        assert_eq!(LambdaEvent::Unknown(json!({})).summary().source, "unknown");
+    }
+
+    #[cfg(feature = "dynamodb")]
+    #[test]
+    fn dynamodb_summary() {
+        let mut event = crate::testing::dynamodb().inserts(2).build();
+        event.records[0].dynamodb.approximate_creation_date_time = Some(100.5);
+        event.records[1].dynamodb.approximate_creation_date_time = Some(90.0);
+        let now = std::time::UNIX_EPOCH + Duration::from_secs(150);
+        let summary = event.summary_at(Some(now));
+        assert_eq!(summary.source, "aws:dynamodb");
+        assert_eq!(summary.record_count, 2);
+        assert_eq!(
+            summary.payload_bytes,
+            serde_json::to_vec(&event).unwrap().len()
+        );
+        assert_eq!(summary.oldest_record_age, Some(Duration::from_secs(60)));
+        assert_eq!(event.summary_at(None).oldest_record_age, None);
+        assert_eq!(LambdaEvent::from(event.clone()).summary().record_count, 2);
+    }
+}
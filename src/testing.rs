@@ -0,0 +1,160 @@
+//! Synthesized events for integration tests, enabled with the `fixtures`
+//! feature. Unlike the fixed JSON in `fixtures`, these are generated to
+//! order, with fresh IDs, consistent ARNs and current timestamps:
+//!
+//! `testing::dynamodb().inserts(3).table("users").build()`
+
+#[cfg(feature = "dynamodb")]
+pub use self::dynamodb::{dynamodb, DynamoDBEventGenerator};
+
+#[cfg(feature = "dynamodb")]
+mod dynamodb {
+    use crate::dynamodb::{
+        AttributeValue, DynamoDBEvent, DynamoDBEventRecord, DynamoDBOperationType,
+    };
+    use std::collections::HashMap;
+
+    /// Starts generating a DynamoDB Streams event.
+    pub fn dynamodb() -> DynamoDBEventGenerator {
+        DynamoDBEventGenerator::default()
+    }
+
+    /// Generates records for a table keyed on `Id`, in the order inserts,
+    /// modifies, removes. Each record has its own item, `item-1`, `item-2`
+    /// and so on, with images matching its operation.
+    #[derive(Debug, Clone)]
+    pub struct DynamoDBEventGenerator {
+        inserts: usize,
+        modifies: usize,
+        removes: usize,
+        table: String,
+        region: String,
+        account_id: String,
+    }
+
+    impl Default for DynamoDBEventGenerator {
+        fn default() -> Self {
+            DynamoDBEventGenerator {
+                inserts: 0,
+                modifies: 0,
+                removes: 0,
+                table: "Table".to_string(),
+                region: "us-east-1".to_string(),
+                account_id: "123456789012".to_string(),
+            }
+        }
+    }
+
+    impl DynamoDBEventGenerator {
+        pub fn inserts(mut self, count: usize) -> Self {
+            self.inserts = count;
+            self
+        }
+
+        pub fn modifies(mut self, count: usize) -> Self {
+            self.modifies = count;
+            self
+        }
+
+        pub fn removes(mut self, count: usize) -> Self {
+            self.removes = count;
+            self
+        }
+
+        pub fn table<S: Into<String>>(mut self, table: S) -> Self {
+            self.table = table.into();
+            self
+        }
+
+        pub fn region<S: Into<String>>(mut self, region: S) -> Self {
+            self.region = region.into();
+            self
+        }
+
+        pub fn account_id<S: Into<String>>(mut self, account_id: S) -> Self {
+            self.account_id = account_id.into();
+            self
+        }
+
+        pub fn build(self) -> DynamoDBEvent {
+            let operations = vec![
+                (DynamoDBOperationType::Insert, self.inserts),
+                (DynamoDBOperationType::Modify, self.modifies),
+                (DynamoDBOperationType::Remove, self.removes),
+            ]
+            .into_iter()
+            .flat_map(|(operation, count)| std::iter::repeat_n(operation, count));
+            let records = operations
+                .enumerate()
+                .map(|(i, operation)| self.record(i + 1, operation))
+                .collect();
+            DynamoDBEvent { records }
+        }
+
+        fn record(&self, n: usize, operation: DynamoDBOperationType) -> DynamoDBEventRecord {
+            let id = AttributeValue::s(format!("item-{}", n));
+            let image = |version: u32| {
+                let mut image = HashMap::new();
+                image.insert("Id".to_string(), id.clone());
+                image.insert(
+                    "Name".to_string(),
+                    AttributeValue::s(format!("Item {} v{}", n, version)),
+                );
+                image.insert("Version".to_string(), AttributeValue::n(version));
+                image
+            };
+            let builder = DynamoDBEventRecord::builder()
+                .table_name(self.table.as_str())
+                .aws_region(self.region.as_str())
+                .account_id(self.account_id.as_str())
+                .key("Id", id.clone());
+            let builder = match operation {
+                DynamoDBOperationType::Insert => builder.new_image(image(1)),
+                DynamoDBOperationType::Modify => builder.old_image(image(1)).new_image(image(2)),
+                _ => builder.old_image(image(1)),
+            };
+            builder.event_name(operation).build()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "dynamodb"))]
+mod test {
+    use crate::dedup::SeenCache;
+    use crate::dynamodb::{compare_sequence_numbers, DynamoDBOperationType};
+    use std::cmp::Ordering;
+
+    #[test]
+    fn generated_events() {
+        let event = super::dynamodb()
+            .inserts(3)
+            .modifies(1)
+            .removes(1)
+            .table("users")
+            .region("eu-west-1")
+            .build();
+        assert_eq!(event.records.len(), 5);
+        assert_eq!(event.inserts().count(), 3);
+        assert!(
+            event
+                .records
+                .iter()
+                .all(|record| record.table_name() == Some("users")
+                    && record.aws_region == "eu-west-1")
+        );
+        assert!(event
+            .records
+            .windows(2)
+            .all(|pair| compare_sequence_numbers(
+                &pair[0].dynamodb.sequence_number,
+                &pair[1].dynamodb.sequence_number
+            ) == Ordering::Less));
+        assert_eq!(SeenCache::new(5).unseen(&event.records).len(), 5);
+
+        let modify = &event.records[3];
+        assert_eq!(modify.event_name, DynamoDBOperationType::Modify);
+        assert_eq!(modify.dynamodb.stream_view_type, "NEW_AND_OLD_IMAGES");
+        assert_ne!(modify.dynamodb.old_image, modify.dynamodb.new_image);
+        assert!(event.records[4].dynamodb.new_image.is_none());
+    }
+}
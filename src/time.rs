@@ -0,0 +1,306 @@
+//! Timestamp conversions and serde adapters for the epoch and RFC 3339
+//! formats AWS events use, enabled with the `chrono` feature. Apply them to
+//! your own types with `#[serde(with = "aws_lambda_events_extended::time::epoch_millis")]`.
+
+use chrono::{DateTime, TimeZone, Utc};
+
+// The `option` submodule of each format, which (de)serializes an
+// `Option<DateTime<Utc>>` with the format's own functions, writing `None` as
+// null.
+macro_rules! option_module {
+    () => {
+        /// The same format for an `Option<DateTime<Utc>>`.
+        pub mod option {
+            use chrono::{DateTime, Utc};
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            struct Time(DateTime<Utc>);
+
+            impl Serialize for Time {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    super::serialize(&self.0, serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for Time {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Time, D::Error> {
+                    super::deserialize(deserializer).map(Time)
+                }
+            }
+
+            pub fn serialize<S>(
+                value: &Option<DateTime<Utc>>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                value.map(Time).serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Ok(Option::<Time>::deserialize(deserializer)?.map(|time| time.0))
+            }
+        }
+    };
+}
+
+const NANOS_PER_SECOND: f64 = 1_000_000_000.0;
+
+/// Converts a fractional UNIX epoch (seconds) into a UTC timestamp.
+pub fn from_float_epoch(epoch: f64) -> Option<DateTime<Utc>> {
+    if !epoch.is_finite() {
+        return None;
+    }
+    let mut secs = epoch.floor();
+    let mut nanos = ((epoch - secs) * NANOS_PER_SECOND).round();
+    // A fraction within half a nanosecond of 1 rounds up to the next second.
+    if nanos >= NANOS_PER_SECOND {
+        secs += 1.0;
+        nanos = 0.0;
+    }
+    Utc.timestamp_opt(secs as i64, nanos as u32).single()
+}
+
+/// Converts a UTC timestamp into a fractional UNIX epoch (seconds).
+pub fn to_float_epoch(time: &DateTime<Utc>) -> f64 {
+    time.timestamp() as f64 + f64::from(time.timestamp_subsec_nanos()) / NANOS_PER_SECOND
+}
+
+/// (De)serializes a `DateTime<Utc>` as a fractional UNIX epoch, e.g.
+/// `1479499740.123`. Use with `#[serde(with = "time::float_epoch")]`, or
+/// `time::float_epoch::option` for an `Option<DateTime<Utc>>`.
+///
+/// This is the format of DynamoDB's ApproximateCreationDateTime. The event
+/// types still keep that field as an f64, converting with `from_float_epoch`
+/// in `DynamoDBStreamRecord::approximate_creation_time`, so no event type
+/// uses these adapters yet.
+pub mod float_epoch {
+    use chrono::{DateTime, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(super::to_float_epoch(time))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let epoch = f64::deserialize(deserializer)?;
+        super::from_float_epoch(epoch)
+            .ok_or_else(|| de::Error::custom(format!("epoch out of range: {}", epoch)))
+    }
+
+    option_module!();
+}
+
+/// (De)serializes a `DateTime<Utc>` as whole seconds since the UNIX epoch,
+/// e.g. `1479499740`, truncating any fraction. `time::epoch_seconds::option`
+/// does the same for an `Option<DateTime<Utc>>`.
+pub mod epoch_seconds {
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(time.timestamp())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Read as a float so fractional input is accepted; every epoch chrono
+        // can represent is an integer an f64 holds exactly.
+        let epoch = f64::deserialize(deserializer)?;
+        Some(epoch)
+            .filter(|epoch| epoch.is_finite())
+            .and_then(|epoch| Utc.timestamp_opt(epoch.trunc() as i64, 0).single())
+            .ok_or_else(|| de::Error::custom(format!("epoch out of range: {}", epoch)))
+    }
+
+    option_module!();
+}
+
+/// (De)serializes a `DateTime<Utc>` as milliseconds since the UNIX epoch,
+/// e.g. `1479499740123`, truncating sub-millisecond precision.
+/// `time::epoch_millis::option` does the same for an `Option<DateTime<Utc>>`.
+pub mod epoch_millis {
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(time.timestamp_millis())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let epoch = i64::deserialize(deserializer)?;
+        Utc.timestamp_millis_opt(epoch)
+            .single()
+            .ok_or_else(|| de::Error::custom(format!("epoch out of range: {}", epoch)))
+    }
+
+    option_module!();
+}
+
+/// (De)serializes a `DateTime<Utc>` as an RFC 3339 string, e.g.
+/// `2016-11-18T20:09:00Z`. Offsets other than `Z` are accepted and converted
+/// to UTC. `time::rfc3339::option` does the same for an `Option<DateTime<Utc>>`.
+pub mod rfc3339 {
+    use chrono::{DateTime, SecondsFormat, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let time = String::deserialize(deserializer)?;
+        DateTime::parse_from_rfc3339(&time)
+            .map(|time| time.with_timezone(&Utc))
+            .map_err(de::Error::custom)
+    }
+
+    option_module!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn float_epoch_roundtrip() {
+        let time = from_float_epoch(1479499740.5).unwrap();
+        assert_eq!(time.timestamp(), 1479499740);
+        assert_eq!(time.timestamp_subsec_millis(), 500);
+        assert_eq!(to_float_epoch(&time), 1479499740.5);
+
+        let carried = from_float_epoch(1.9999999999).unwrap();
+        assert_eq!(carried.timestamp(), 2);
+        assert_eq!(carried.timestamp_subsec_nanos(), 0);
+        let negative = from_float_epoch(-0.0000000001).unwrap();
+        assert_eq!(
+            (negative.timestamp(), negative.timestamp_subsec_nanos()),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn serde_helpers() {
+        use serde::{Deserialize, Serialize};
+        use serde_json::json;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Times {
+            #[serde(with = "super::epoch_seconds")]
+            seconds: DateTime<Utc>,
+            #[serde(with = "super::epoch_millis")]
+            millis: DateTime<Utc>,
+            #[serde(with = "super::float_epoch")]
+            float: DateTime<Utc>,
+            #[serde(with = "super::rfc3339")]
+            rfc3339: DateTime<Utc>,
+        }
+
+        let json = json!({
+            "seconds": 1479499740,
+            "millis": 1479499740500i64,
+            "float": 1479499740.5,
+            "rfc3339": "2016-11-18T20:09:00.500Z",
+        });
+        let times: Times = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(times.seconds.timestamp(), 1479499740);
+        assert_eq!(times.millis, times.float);
+        assert_eq!(times.rfc3339, times.float);
+        assert_eq!(serde_json::to_value(&times).unwrap(), json);
+
+        let offset: Times = serde_json::from_value(json!({
+            "seconds": 1_600_000_000.5,
+            "millis": 1479499740500i64,
+            "float": 1479499740.5,
+            "rfc3339": "2016-11-18T21:09:00.500+01:00",
+        }))
+        .unwrap();
+        assert_eq!(offset.rfc3339, times.float);
+        assert_eq!(offset.seconds.timestamp(), 1_600_000_000);
+        assert_eq!(offset.seconds.timestamp_subsec_nanos(), 0);
+
+        let invalid = json!({
+            "seconds": 1479499740,
+            "millis": 1479499740500i64,
+            "float": 1479499740.5,
+            "rfc3339": "yesterday",
+        });
+        assert!(serde_json::from_value::<Times>(invalid).is_err());
+        let missing = json!({"millis": 1479499740500i64, "float": 1.5, "rfc3339": null});
+        assert!(serde_json::from_value::<Times>(missing).is_err());
+    }
+
+    #[test]
+    fn optional_serde_helpers() {
+        use serde::{Deserialize, Serialize};
+        use serde_json::json;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Times {
+            #[serde(with = "super::epoch_seconds::option")]
+            seconds: Option<DateTime<Utc>>,
+            #[serde(with = "super::epoch_millis::option")]
+            millis: Option<DateTime<Utc>>,
+            #[serde(with = "super::float_epoch::option")]
+            float: Option<DateTime<Utc>>,
+            #[serde(with = "super::rfc3339::option")]
+            rfc3339: Option<DateTime<Utc>>,
+        }
+
+        let json = json!({
+            "seconds": 1479499740,
+            "millis": 1479499740500i64,
+            "float": 1479499740.5,
+            "rfc3339": "2016-11-18T20:09:00.500Z",
+        });
+        let times: Times = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(times.seconds.unwrap().timestamp(), 1479499740);
+        assert_eq!(times.millis, times.float);
+        assert_eq!(times.rfc3339, times.float);
+        assert_eq!(serde_json::to_value(&times).unwrap(), json);
+
+        let nulls = json!({"seconds": null, "millis": null, "float": null, "rfc3339": null});
+        let empty: Times = serde_json::from_value(nulls.clone()).unwrap();
+        assert_eq!(
+            empty,
+            Times {
+                seconds: None,
+                millis: None,
+                float: None,
+                rfc3339: None,
+            }
+        );
+        assert_eq!(serde_json::to_value(&empty).unwrap(), nulls);
+        assert!(serde_json::from_value::<Times>(json!({
+            "seconds": null, "millis": null, "float": null, "rfc3339": "yesterday"
+        }))
+        .is_err());
+    }
+}
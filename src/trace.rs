@@ -0,0 +1,244 @@
+//! Distributed tracing context, in AWS X-Ray and W3C `traceparent` forms.
+
+use crate::context::LambdaContext;
+use crate::event::LambdaEvent;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A trace position as carried by X-Ray trace headers, e.g.
+/// `Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+    /// The X-Ray trace ID, `1-{8 hex digit epoch}-{24 hex digits}`.
+    pub trace_id: String,
+    /// The 16 hex digit ID of the parent segment.
+    pub parent_id: Option<String>,
+    /// The sampling decision, if one was made upstream.
+    pub sampled: Option<bool>,
+}
+
+impl TraceContext {
+    /// Converts a W3C `traceparent` header, e.g.
+    /// `00-5759e988bd862e3fe1be46a994272793-53995c3f42cd8ad8-01`.
+    pub fn from_traceparent(traceparent: &str) -> Result<TraceContext, TraceContextError> {
+        let error = || TraceContextError {
+            header: traceparent.to_string(),
+        };
+        let parts: Vec<&str> = traceparent.split('-').collect();
+        match parts[..] {
+            ["00", trace_id, parent_id, flags]
+                if is_hex(trace_id, 32) && is_hex(parent_id, 16) && is_hex(flags, 2) =>
+            {
+                let flags = u8::from_str_radix(flags, 16).map_err(|_| error())?;
+                Ok(TraceContext {
+                    trace_id: format!("1-{}-{}", &trace_id[..8], &trace_id[8..]),
+                    parent_id: Some(parent_id.to_string()),
+                    sampled: Some(flags & 1 == 1),
+                })
+            }
+            _ => Err(error()),
+        }
+    }
+
+    /// The W3C `traceparent` header for this context. `None` without a
+    /// parent ID, which `traceparent` requires.
+    pub fn to_traceparent(&self) -> Option<String> {
+        let trace_id = self.trace_id.strip_prefix("1-")?.replace('-', "");
+        Some(format!(
+            "00-{}-{}-{}",
+            trace_id,
+            self.parent_id.as_ref()?,
+            if self.sampled == Some(true) {
+                "01"
+            } else {
+                "00"
+            }
+        ))
+    }
+}
+
+fn is_hex(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Parses an X-Ray trace header. Unknown fields, such as `Lineage`, are
+/// ignored.
+impl FromStr for TraceContext {
+    type Err = TraceContextError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || TraceContextError {
+            header: s.to_string(),
+        };
+        let mut trace_id = None;
+        let mut parent_id = None;
+        let mut sampled = None;
+        for field in s
+            .split(';')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+        {
+            let (name, value) = field.split_once('=').ok_or_else(error)?;
+            match name {
+                "Root" => {
+                    let mut parts = value.split('-');
+                    let valid = parts.next() == Some("1")
+                        && parts.next().is_some_and(|epoch| is_hex(epoch, 8))
+                        && parts.next().is_some_and(|id| is_hex(id, 24))
+                        && parts.next().is_none();
+                    if !valid {
+                        return Err(error());
+                    }
+                    trace_id = Some(value.to_string());
+                }
+                "Parent" if is_hex(value, 16) => parent_id = Some(value.to_string()),
+                "Parent" => return Err(error()),
+                "Sampled" => {
+                    sampled = match value {
+                        "1" => Some(true),
+                        "0" => Some(false),
+                        // "?" asks downstream to decide.
+                        _ => None,
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(TraceContext {
+            trace_id: trace_id.ok_or_else(error)?,
+            parent_id,
+            sampled,
+        })
+    }
+}
+
+/// Formats as an X-Ray trace header.
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Root={}", self.trace_id)?;
+        if let Some(parent_id) = &self.parent_id {
+            write!(f, ";Parent={}", parent_id)?;
+        }
+        match self.sampled {
+            Some(true) => f.write_str(";Sampled=1"),
+            Some(false) => f.write_str(";Sampled=0"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returned when a trace header is malformed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceContextError {
+    header: String,
+}
+
+impl fmt::Display for TraceContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid trace header: {:?}", self.header)
+    }
+}
+
+impl Error for TraceContextError {}
+
+/// Types that carry the trace context of the invocation or message that
+/// produced them.
+pub trait ExtractTrace {
+    /// The trace context, or `None` if there is none or it is malformed.
+    fn trace_context(&self) -> Option<TraceContext>;
+}
+
+/// The trace Lambda started for the invocation, from the
+/// `Lambda-Runtime-Trace-Id` header.
+impl ExtractTrace for LambdaContext {
+    fn trace_context(&self) -> Option<TraceContext> {
+        self.xray_trace_id.as_deref()?.parse().ok()
+    }
+}
+
+/// The trace of the message behind the first record of an unknown payload:
+/// SQS's `AWSTraceHeader` system attribute, or an `AWSTraceHeader` message
+/// attribute on an SNS notification. DynamoDB stream records carry no trace.
+impl ExtractTrace for LambdaEvent {
+    fn trace_context(&self) -> Option<TraceContext> {
+        match self {
+            #[cfg(feature = "dynamodb")]
+            LambdaEvent::DynamoDB(_) => None,
+            LambdaEvent::Unknown(value) => {
+                let record = &value["Records"][0];
+                record["attributes"]["AWSTraceHeader"]
+                    .as_str()
+                    .or_else(|| {
+                        record["Sns"]["MessageAttributes"]["AWSTraceHeader"]["Value"].as_str()
+                    })?
+                    .parse()
+                    .ok()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn trace_headers() {
+        let header = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1";
+        let trace: TraceContext = header.parse().unwrap();
+        assert_eq!(trace.trace_id, "1-5759e988-bd862e3fe1be46a994272793");
+        assert_eq!(trace.parent_id.as_deref(), Some("53995c3f42cd8ad8"));
+        assert_eq!(trace.sampled, Some(true));
+        assert_eq!(trace.to_string(), header);
+
+        let traceparent = "00-5759e988bd862e3fe1be46a994272793-53995c3f42cd8ad8-01";
+        assert_eq!(trace.to_traceparent().as_deref(), Some(traceparent));
+        assert_eq!(TraceContext::from_traceparent(traceparent).unwrap(), trace);
+
+        let root: TraceContext = "Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=?;Lineage=a:1"
+            .parse()
+            .unwrap();
+        assert_eq!((root.parent_id.clone(), root.sampled), (None, None));
+        assert_eq!(root.to_traceparent(), None);
+
+        for bad in &["", "Parent=53995c3f42cd8ad8", "Root=1-xyz-abc", "Root"] {
+            assert!(bad.parse::<TraceContext>().is_err(), "{:?}", bad);
+        }
+        assert!(TraceContext::from_traceparent("01-00-00-00").is_err());
+
+        let context = LambdaContext {
+            xray_trace_id: Some(header.to_string()),
+            ..LambdaContext::default()
+        };
+        assert_eq!(context.trace_context(), Some(trace));
+    }
+
+    #[test]
+    fn message_traces() {
+        let header = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1";
+        let sqs = LambdaEvent::Unknown(json!({"Records": [{
+            "messageId": "059f36b4-87a3-44ab-83d2-661975830a7d",
+            "body": "hi",
+            "attributes": {
+                "ApproximateReceiveCount": "1",
+                "AWSTraceHeader": header
+            },
+            "eventSource": "aws:sqs"
+        }]}));
+        assert_eq!(sqs.trace_context(), header.parse().ok());
+
+        let sns = LambdaEvent::Unknown(json!({"Records": [{
+            "EventSource": "aws:sns",
+            "Sns": {"MessageAttributes": {
+                "AWSTraceHeader": {"Type": "String", "Value": header}
+            }}
+        }]}));
+        assert_eq!(sns.trace_context(), header.parse().ok());
+
+        let untraced = LambdaEvent::Unknown(json!({"Records": [{"eventSource": "aws:sqs"}]}));
+        assert_eq!(untraced.trace_context(), None);
+        assert_eq!(LambdaEvent::Unknown(json!({})).trace_context(), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Detection of keep-warm invocations, so handlers can return early instead
+//! of treating the ping as a real event.
+
+use crate::event::LambdaEvent;
+use serde_json::Value;
+
+/// A recognized keep-warm payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarmupEvent {
+    /// `{"source": "serverless-plugin-warmup"}`, from serverless-plugin-warmup.
+    ServerlessPluginWarmup,
+    /// `{"warmer": true, "concurrency": 3}`, from lambda-warmer, which fans
+    /// out to warm `concurrency` instances.
+    LambdaWarmer { concurrency: Option<u64> },
+    /// An EventBridge (CloudWatch Events) schedule whose detail is marked
+    /// with `"warmup": true`.
+    ScheduledPing,
+}
+
+impl WarmupEvent {
+    /// Recognizes `payload` as a keep-warm ping, or `None` for anything else.
+    pub fn detect(payload: &Value) -> Option<WarmupEvent> {
+        if payload["source"] == "serverless-plugin-warmup" {
+            return Some(WarmupEvent::ServerlessPluginWarmup);
+        }
+        if payload["warmer"] == true {
+            return Some(WarmupEvent::LambdaWarmer {
+                concurrency: payload["concurrency"].as_u64(),
+            });
+        }
+        if payload["source"] == "aws.events"
+            && payload["detail-type"] == "Scheduled Event"
+            && payload["detail"]["warmup"] == true
+        {
+            return Some(WarmupEvent::ScheduledPing);
+        }
+        None
+    }
+}
+
+impl LambdaEvent {
+    /// Whether this is a keep-warm ping rather than a real event. Events of a
+    /// known source never are.
+    pub fn is_warmup(&self) -> bool {
+        self.warmup().is_some()
+    }
+
+    pub fn warmup(&self) -> Option<WarmupEvent> {
+        match self {
+            LambdaEvent::Unknown(value) => WarmupEvent::detect(value),
+            #[cfg(feature = "dynamodb")]
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_pings() {
+        let detect = |payload: Value| {
+            serde_json::from_value::<LambdaEvent>(payload)
+                .unwrap()
+                .warmup()
+        };
+        assert_eq!(
+            detect(json!({"source": "serverless-plugin-warmup"})),
+            Some(WarmupEvent::ServerlessPluginWarmup)
+        );
+        assert_eq!(
+            detect(json!({"warmer": true, "concurrency": 3})),
+            Some(WarmupEvent::LambdaWarmer {
+                concurrency: Some(3)
+            })
+        );
+        assert_eq!(
+            detect(json!({
+                "source": "aws.events",
+                "detail-type": "Scheduled Event",
+                "detail": {"warmup": true}
+            })),
+            Some(WarmupEvent::ScheduledPing)
+        );
+        assert_eq!(
+            detect(json!({"source": "aws.events", "detail-type": "Scheduled Event", "detail": {}})),
+            None
+        );
+        assert_eq!(detect(json!({"warmer": "yes"})), None);
+        assert!(!LambdaEvent::Unknown(json!([])).is_warmup());
+    }
+}